@@ -0,0 +1,380 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Read},
+    path::Path,
+    thread,
+};
+
+/// How much of a stream each thread is given at a time when counting in parallel
+const CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum TrackerError {
+    Io(io::Error),
+    /// A byte that isn't `(`, `)` or whitespace, and its offset in the input
+    InvalidInstruction {
+        byte: u8,
+        offset: usize,
+    },
+}
+
+impl fmt::Display for TrackerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read instructions: {error}"),
+            Self::InvalidInstruction { byte, offset } if byte.is_ascii() => {
+                write!(
+                    f,
+                    "Invalid instruction {:?} at offset {offset}",
+                    *byte as char
+                )
+            }
+            Self::InvalidInstruction { byte, offset } => {
+                write!(
+                    f,
+                    "Invalid instruction byte 0x{byte:02x} at offset {offset}"
+                )
+            }
+        }
+    }
+}
+
+impl From<io::Error> for TrackerError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Follows Santa around the building one instruction at a time
+///
+/// Positions are 1-based instruction numbers, like the puzzle uses
+#[derive(Debug, Default)]
+pub struct FloorTracker {
+    floor: isize,
+    /// Number of instructions followed so far
+    position: usize,
+    /// Number of bytes consumed so far, including whitespace
+    offset: usize,
+    basement_entries: Vec<usize>,
+    highest: (isize, usize),
+    lowest: (isize, usize),
+    time_on_floor: BTreeMap<isize, usize>,
+}
+
+impl FloorTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, TrackerError> {
+        let mut tracker = Self::new();
+        let mut buffer = [0; 64 * 1024];
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            };
+            tracker.feed(&buffer[..read])?;
+        }
+        Ok(tracker)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TrackerError> {
+        Self::from_reader(fs::File::open(path)?)
+    }
+
+    /// Follow every instruction in a chunk of input
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<(), TrackerError> {
+        bytes.iter().try_for_each(|byte| self.step(*byte))
+    }
+
+    /// Follow a single instruction, skipping over whitespace
+    pub fn step(&mut self, byte: u8) -> Result<(), TrackerError> {
+        let offset = self.offset;
+        self.offset += 1;
+
+        let previous = self.floor;
+        self.floor += match byte {
+            b'(' => 1,
+            b')' => -1,
+            b if b.is_ascii_whitespace() => return Ok(()),
+            _ => return Err(TrackerError::InvalidInstruction { byte, offset }),
+        };
+        self.position += 1;
+
+        if previous >= 0 && self.floor < 0 {
+            self.basement_entries.push(self.position);
+        }
+        if self.floor > self.highest.0 {
+            self.highest = (self.floor, self.position);
+        }
+        if self.floor < self.lowest.0 {
+            self.lowest = (self.floor, self.position);
+        }
+        *self.time_on_floor.entry(self.floor).or_insert(0) += 1;
+
+        Ok(())
+    }
+
+    pub fn floor(&self) -> isize {
+        self.floor
+    }
+
+    /// The position of the instruction that first takes Santa into the basement
+    pub fn first_basement(&self) -> Option<usize> {
+        self.basement_entries.first().copied()
+    }
+
+    /// Every position where Santa goes from the ground floor into the basement
+    pub fn basement_entries(&self) -> &[usize] {
+        &self.basement_entries
+    }
+
+    /// The highest floor reached, and the position it was first reached at, which is 0
+    /// if Santa never went above the ground floor
+    pub fn highest(&self) -> (isize, usize) {
+        self.highest
+    }
+
+    /// The lowest floor reached, and the position it was first reached at, which is 0
+    /// if Santa never went below the ground floor
+    pub fn lowest(&self) -> (isize, usize) {
+        self.lowest
+    }
+
+    /// How many instructions Santa finished on each floor
+    pub fn time_on_floor(&self) -> &BTreeMap<isize, usize> {
+        &self.time_on_floor
+    }
+}
+
+/// What a chunk of instructions does to the floor, which can be worked out
+/// independently of every other chunk and then combined in order
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+struct Summary {
+    /// How far the chunk moves Santa up or down
+    delta: isize,
+    /// The lowest floor reached during the chunk, relative to where it started
+    lowest: isize,
+    /// How many instructions (not whitespace) the chunk contains
+    instructions: usize,
+}
+
+impl Summary {
+    /// Summarise a chunk that starts `offset` bytes into the input
+    fn of(bytes: &[u8], offset: usize) -> Result<Self, TrackerError> {
+        let mut summary = Self::default();
+        for (i, &byte) in bytes.iter().enumerate() {
+            summary.delta += match byte {
+                b'(' => 1,
+                b')' => -1,
+                b if b.is_ascii_whitespace() => continue,
+                _ => {
+                    return Err(TrackerError::InvalidInstruction {
+                        byte,
+                        offset: offset + i,
+                    })
+                }
+            };
+            summary.instructions += 1;
+            summary.lowest = summary.lowest.min(summary.delta);
+        }
+        Ok(summary)
+    }
+}
+
+/// The answers to both parts of the puzzle
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct FloorCount {
+    pub floor: isize,
+    pub first_basement: Option<usize>,
+}
+
+impl FloorCount {
+    /// Count a huge stream of instructions, summarising chunks of it on every core
+    pub fn from_reader(reader: impl Read) -> Result<Self, TrackerError> {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::from_reader_chunked(reader, threads, CHUNK_SIZE)
+    }
+
+    fn from_reader_chunked(
+        mut reader: impl Read,
+        threads: usize,
+        chunk_size: usize,
+    ) -> Result<Self, TrackerError> {
+        let mut count = Self::default();
+        let mut position = 0;
+        let mut offset = 0;
+        let mut block = vec![0; threads * chunk_size];
+
+        loop {
+            // Fill the block as far as possible so every thread gets a full chunk
+            let mut filled = 0;
+            while filled < block.len() {
+                match reader.read(&mut block[filled..]) {
+                    Ok(0) => break,
+                    Ok(read) => filled += read,
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            if filled == 0 {
+                return Ok(count);
+            }
+
+            let chunks: Vec<&[u8]> = block[..filled].chunks(chunk_size).collect();
+            let summaries: Vec<Result<Summary, TrackerError>> = thread::scope(|scope| {
+                let handles: Vec<_> = chunks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        scope.spawn(move || Summary::of(chunk, offset + i * chunk_size))
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .collect()
+            });
+
+            // Combine the summaries in order
+            for (chunk, summary) in chunks.into_iter().zip(summaries) {
+                let summary = summary?;
+
+                // Only the chunk where Santa first dips below the ground floor
+                // needs to be walked through again to find the exact position
+                if count.first_basement.is_none() && count.floor + summary.lowest < 0 {
+                    let mut floor = count.floor;
+                    let mut instruction = position;
+                    for byte in chunk {
+                        floor += match byte {
+                            b'(' => 1,
+                            b')' => -1,
+                            _ => continue,
+                        };
+                        instruction += 1;
+                        if floor < 0 {
+                            count.first_basement = Some(instruction);
+                            break;
+                        }
+                    }
+                }
+
+                count.floor += summary.delta;
+                position += summary.instructions;
+                offset += chunk.len();
+            }
+
+            if filled < block.len() {
+                return Ok(count);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn track(instructions: &str) -> FloorTracker {
+        FloorTracker::from_reader(instructions.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn final_floor() {
+        assert_eq!(track("(())").floor(), 0);
+        assert_eq!(track("()()").floor(), 0);
+        assert_eq!(track("(((").floor(), 3);
+        assert_eq!(track("(()(()(").floor(), 3);
+        assert_eq!(track("))(((((").floor(), 3);
+        assert_eq!(track("())").floor(), -1);
+        assert_eq!(track("))(").floor(), -1);
+        assert_eq!(track(")))").floor(), -3);
+        assert_eq!(track(")())())\n").floor(), -3);
+    }
+
+    #[test]
+    fn basement() {
+        assert_eq!(track(")").first_basement(), Some(1));
+        assert_eq!(track("()())").first_basement(), Some(5));
+        assert_eq!(track("((()").first_basement(), None);
+
+        assert_eq!(track(")()))(((()").basement_entries(), &[1, 3]);
+    }
+
+    #[test]
+    fn statistics() {
+        let tracker = track("((()))))(");
+        assert_eq!(tracker.highest(), (3, 3));
+        assert_eq!(tracker.lowest(), (-2, 8));
+        assert_eq!(
+            tracker
+                .time_on_floor()
+                .clone()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![(-2, 1), (-1, 2), (0, 1), (1, 2), (2, 2), (3, 1)]
+        );
+
+        // Nothing is reached without any instructions
+        let empty = track("\n");
+        assert_eq!(empty.highest(), (0, 0));
+        assert_eq!(empty.lowest(), (0, 0));
+        assert!(empty.time_on_floor().is_empty());
+    }
+
+    #[test]
+    fn invalid_instruction() {
+        let error = FloorTracker::from_reader("(( )x(".as_bytes()).unwrap_err();
+        assert!(matches!(
+            error,
+            TrackerError::InvalidInstruction {
+                byte: b'x',
+                offset: 4
+            }
+        ));
+        assert_eq!(error.to_string(), "Invalid instruction 'x' at offset 4");
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        // Wander around near the ground floor so the basement is entered several times
+        let mut state: u64 = 7;
+        let instructions: String = (0..100_000)
+            .map(|i| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                let bias = if i < 50_000 { 33 } else { 31 };
+                if state >> 58 < bias {
+                    '('
+                } else {
+                    ')'
+                }
+            })
+            .collect();
+        let tracker = track(&instructions);
+        assert!(tracker.basement_entries().len() > 1);
+
+        for (threads, chunk_size) in [(1, 100_000), (4, 1000), (3, 7), (8, 4096)] {
+            let count =
+                FloorCount::from_reader_chunked(instructions.as_bytes(), threads, chunk_size)
+                    .unwrap();
+            assert_eq!(count.floor, tracker.floor());
+            assert_eq!(count.first_basement, tracker.first_basement());
+        }
+    }
+
+    #[test]
+    fn parallel_errors() {
+        let error = FloorCount::from_reader_chunked("((((\n))))((x".as_bytes(), 2, 3).unwrap_err();
+        assert!(matches!(
+            error,
+            TrackerError::InvalidInstruction {
+                byte: b'x',
+                offset: 11
+            }
+        ));
+    }
+}
@@ -1,4 +1,36 @@
-use std::fs;
+use std::{env, fmt, fs};
+
+use serde::Serialize;
+
+#[derive(Debug, PartialEq)]
+enum PresentError {
+    /// The line wasn't in the form `LxWxH`
+    Malformed(String),
+    /// One of the dimensions wasn't a positive whole number
+    InvalidDimension(String),
+    /// The present is so big the amount of paper or ribbon doesn't fit in a `usize`
+    Overflow,
+}
+
+/// A problem with a particular line of the list
+#[derive(Debug, PartialEq)]
+struct LineError {
+    line: usize,
+    error: PresentError,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+        match &self.error {
+            PresentError::Malformed(text) => write!(f, "expected LxWxH but found {text:?}"),
+            PresentError::InvalidDimension(text) => {
+                write!(f, "{text:?} is not a valid dimension")
+            }
+            PresentError::Overflow => write!(f, "present is too large to wrap"),
+        }
+    }
+}
 
 struct Present {
     length: usize,
@@ -6,54 +38,225 @@ struct Present {
     height: usize,
 }
 
+impl TryFrom<&str> for Present {
+    type Error = PresentError;
+
+    fn try_from(dimensions: &str) -> Result<Self, Self::Error> {
+        let dimensions: Vec<&str> = dimensions.trim().split('x').collect();
+        let [length, width, height] = dimensions.as_slice() else {
+            return Err(PresentError::Malformed(dimensions.join("x")));
+        };
+
+        let parse = |dimension: &str| match dimension.parse() {
+            Ok(0) | Err(_) => Err(PresentError::InvalidDimension(dimension.to_owned())),
+            Ok(value) => Ok(value),
+        };
+        Ok(Self {
+            length: parse(length)?,
+            width: parse(width)?,
+            height: parse(height)?,
+        })
+    }
+}
+
 impl Present {
-    fn build(dimensions: &str) -> Self {
-        let dimensions: Vec<usize> = dimensions.split('x').map(|d| d.parse().unwrap()).collect();
-        Self {
-            length: dimensions[0],
-            width: dimensions[1],
-            height: dimensions[2],
-        }
+    fn sides(&self) -> Option<[usize; 3]> {
+        Some([
+            self.length.checked_mul(self.width)?,
+            self.width.checked_mul(self.height)?,
+            self.height.checked_mul(self.length)?,
+        ])
+    }
+
+    /// Area of paper needed to cover every side, not including slack
+    fn calculate_surface_area(&self) -> Option<usize> {
+        self.sides()?.iter().try_fold(0_usize, |total, side| {
+            total.checked_add(side.checked_mul(2)?)
+        })
+    }
+
+    /// Extra paper equal to the area of the smallest side
+    fn calculate_slack(&self) -> Option<usize> {
+        self.sides()?.into_iter().min()
     }
 
-    fn calculate_paper_required(&self) -> usize {
-        let sides = vec![
-            self.length * self.width,
-            self.width * self.height,
-            self.height * self.length,
-        ];
-        let smallest = sides.iter().min().unwrap();
-        sides.iter().map(|side| side * 2).sum::<usize>() + smallest
+    fn calculate_paper_required(&self) -> Option<usize> {
+        self.calculate_surface_area()?
+            .checked_add(self.calculate_slack()?)
     }
 
-    fn calculate_ribbon_required(&self) -> usize {
-        let mut sides = vec![self.length * 2, self.width * 2, self.height * 2];
+    /// Ribbon to go around the smallest perimeter of the present
+    fn calculate_wrap_length(&self) -> Option<usize> {
+        let mut sides = [self.length, self.width, self.height];
         sides.sort_unstable();
-        let wrap_length: usize = sides.into_iter().take(2).sum();
-        let bow_length = self.length * self.width * self.height;
-        wrap_length + bow_length
+        sides[0].checked_add(sides[1])?.checked_mul(2)
+    }
+
+    /// Ribbon for the bow, equal to the volume of the present
+    fn calculate_bow_length(&self) -> Option<usize> {
+        self.length
+            .checked_mul(self.width)?
+            .checked_mul(self.height)
+    }
+
+    fn calculate_ribbon_required(&self) -> Option<usize> {
+        self.calculate_wrap_length()?
+            .checked_add(self.calculate_bow_length()?)
+    }
+}
+
+/// Everything needed to wrap one present
+#[derive(Serialize, Debug, PartialEq)]
+struct Materials {
+    line: usize,
+    length: usize,
+    width: usize,
+    height: usize,
+    paper: usize,
+    slack: usize,
+    ribbon: usize,
+    bow: usize,
+}
+
+impl Materials {
+    fn new(line: usize, present: &Present) -> Option<Self> {
+        Some(Self {
+            line,
+            length: present.length,
+            width: present.width,
+            height: present.height,
+            paper: present.calculate_paper_required()?,
+            slack: present.calculate_slack()?,
+            ribbon: present.calculate_ribbon_required()?,
+            bow: present.calculate_bow_length()?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct BillOfMaterials {
+    presents: Vec<Materials>,
+    total_paper: usize,
+    total_ribbon: usize,
+}
+
+impl TryFrom<&str> for BillOfMaterials {
+    type Error = LineError;
+
+    fn try_from(list: &str) -> Result<Self, Self::Error> {
+        let presents = list
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, text)| {
+                let line = i + 1;
+                Present::try_from(text)
+                    .and_then(|present| {
+                        Materials::new(line, &present).ok_or(PresentError::Overflow)
+                    })
+                    .map_err(|error| LineError { line, error })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total = |amount: fn(&Materials) -> usize| {
+            presents.iter().try_fold(0_usize, |total, materials| {
+                total.checked_add(amount(materials)).ok_or(LineError {
+                    line: materials.line,
+                    error: PresentError::Overflow,
+                })
+            })
+        };
+
+        Ok(Self {
+            total_paper: total(|materials| materials.paper)?,
+            total_ribbon: total(|materials| materials.ribbon)?,
+            presents,
+        })
+    }
+}
+
+impl BillOfMaterials {
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("line,length,width,height,paper,slack,ribbon,bow\n");
+        for m in &self.presents {
+            csv += &format!(
+                "{},{},{},{},{},{},{},{}\n",
+                m.line, m.length, m.width, m.height, m.paper, m.slack, m.ribbon, m.bow
+            );
+        }
+        csv
     }
 }
 
 fn main() {
     let input = fs::read_to_string("input.txt").expect("Failed to read input file");
 
-    let presents: Vec<Present> = input
-        .lines()
-        .take_while(|line| !line.is_empty())
-        .map(Present::build)
-        .collect();
+    let bill = BillOfMaterials::try_from(input.as_str()).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    });
+
+    match env::args().nth(1).as_deref() {
+        Some("--csv") => print!("{}", bill.to_csv()),
+        Some("--json") => println!("{}", serde_json::to_string_pretty(&bill).unwrap()),
+        _ => {
+            println!(
+                "The total amount of paper required is: {}",
+                bill.total_paper
+            );
+            println!(
+                "The total amount of ribbon required is: {}",
+                bill.total_ribbon
+            );
+        }
+    }
+}
 
-    let paper_required: usize = presents
-        .iter()
-        .map(|present| present.calculate_paper_required())
-        .sum();
+#[cfg(test)]
+mod test {
+    use crate::*;
 
-    let ribbon_required: usize = presents
-        .iter()
-        .map(|present| present.calculate_ribbon_required())
-        .sum();
+    #[test]
+    fn materials() {
+        let bill = BillOfMaterials::try_from("2x3x4\n1x1x10\n").unwrap();
+        assert_eq!(
+            bill.presents[0],
+            Materials {
+                line: 1,
+                length: 2,
+                width: 3,
+                height: 4,
+                paper: 58,
+                slack: 6,
+                ribbon: 34,
+                bow: 24,
+            }
+        );
+        assert_eq!(bill.presents[1].paper, 43);
+        assert_eq!(bill.presents[1].ribbon, 14);
+        assert_eq!(bill.total_paper, 101);
+        assert_eq!(bill.total_ribbon, 48);
+        assert_eq!(
+            bill.to_csv(),
+            "line,length,width,height,paper,slack,ribbon,bow\n1,2,3,4,58,6,34,24\n2,1,1,10,43,1,14,10\n"
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let error = |list| BillOfMaterials::try_from(list).err().unwrap();
 
-    println!("The total amount of paper required is: {paper_required}");
-    println!("The total amount of ribbon required is: {ribbon_required}");
+        assert_eq!(
+            error("2x3x4\n2x3\n").to_string(),
+            "Line 2: expected LxWxH but found \"2x3\""
+        );
+        assert_eq!(
+            error("2x3x4\n\n1xAx1").to_string(),
+            "Line 3: \"A\" is not a valid dimension"
+        );
+        assert_eq!(
+            error(format!("1x1x1\n{0}x{0}x2", usize::MAX).as_str()).error,
+            PresentError::Overflow
+        );
+    }
 }
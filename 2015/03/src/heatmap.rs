@@ -0,0 +1,248 @@
+use std::io::{self, Write};
+
+use crate::{Delivery, Location};
+
+type Colour = [u8; 3];
+
+/// Drawn over the house everyone starts at
+const ORIGIN: Colour = [255, 255, 255];
+
+/// Colours for each deliverer, reused if there are more deliverers than colours
+const PALETTE: [Colour; 6] = [
+    [230, 57, 70],
+    [69, 123, 230],
+    [42, 185, 99],
+    [244, 162, 40],
+    [155, 89, 182],
+    [26, 188, 196],
+];
+
+/// Cold to hot, for houses that got only a few presents up to the house that got the most
+const GRADIENT: [Colour; 4] = [[20, 30, 90], [120, 40, 140], [220, 60, 50], [255, 220, 80]];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Colouring {
+    /// Colour each house by how many presents it got
+    Visits,
+    /// Colour each house by who visited it, mixing colours where deliverers shared a house
+    Deliverer,
+}
+
+/// The smallest rectangle containing every visited house
+#[derive(PartialEq, Debug)]
+struct Bounds {
+    min: Location,
+    max: Location,
+}
+
+impl Bounds {
+    fn of(delivery: &Delivery) -> Self {
+        let mut bounds = Self {
+            min: Location(0, 0),
+            max: Location(0, 0),
+        };
+        for location in delivery.houses.keys() {
+            bounds.min = Location(bounds.min.0.min(location.0), bounds.min.1.min(location.1));
+            bounds.max = Location(bounds.max.0.max(location.0), bounds.max.1.max(location.1));
+        }
+        bounds
+    }
+
+    fn width(&self) -> usize {
+        self.max.0.abs_diff(self.min.0) + 1
+    }
+
+    fn height(&self) -> usize {
+        self.max.1.abs_diff(self.min.1) + 1
+    }
+}
+
+/// A grid of colours covering the bounding box of a delivery, with north at the top
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    /// `None` for houses nobody visited
+    cells: Vec<Option<Colour>>,
+    origin: (usize, usize),
+}
+
+impl Heatmap {
+    pub fn new(delivery: &Delivery, colouring: Colouring) -> Self {
+        let bounds = Bounds::of(delivery);
+        let (width, height) = (bounds.width(), bounds.height());
+        let position = |location: &Location| {
+            (
+                location.0.abs_diff(bounds.min.0),
+                location.1.abs_diff(bounds.max.1),
+            )
+        };
+
+        // Log scale, otherwise the origin drowns out everything else
+        let most = delivery
+            .houses
+            .values()
+            .map(|visits| visits.iter().sum::<usize>())
+            .max()
+            .unwrap_or(1);
+        let heat = |presents: usize| {
+            if most <= 1 {
+                0.0
+            } else {
+                (presents as f64).ln() / (most as f64).ln()
+            }
+        };
+
+        let mut cells = vec![None; width * height];
+        for (location, visits) in &delivery.houses {
+            let (x, y) = position(location);
+            cells[y * width + x] = Some(match colouring {
+                Colouring::Visits => gradient(heat(visits.iter().sum())),
+                Colouring::Deliverer => mix(visits
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &count)| count > 0)
+                    .map(|(deliverer, _)| PALETTE[deliverer % PALETTE.len()])),
+            });
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+            origin: position(&Location(0, 0)),
+        }
+    }
+
+    fn colour(&self, x: usize, y: usize) -> Option<Colour> {
+        if (x, y) == self.origin {
+            return Some(ORIGIN);
+        }
+        self.cells[y * self.width + x]
+    }
+
+    /// Render using ANSI true colour blocks, two characters wide so houses come out square
+    pub fn to_terminal(&self) -> String {
+        let mut output = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                match self.colour(x, y) {
+                    Some(_) if (x, y) == self.origin => output += "\x1b[0m()",
+                    Some([r, g, b]) => output += &format!("\x1b[48;2;{r};{g};{b}m  "),
+                    None => output += "\x1b[0m  ",
+                }
+            }
+            output += "\x1b[0m\n";
+        }
+        output
+    }
+
+    /// Every pixel as RGB bytes, with each house drawn as a `scale` by `scale` square
+    fn pixels(&self, scale: usize) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.width * self.height * scale * scale * 3);
+        for y in 0..self.height * scale {
+            for x in 0..self.width * scale {
+                pixels.extend(self.colour(x / scale, y / scale).unwrap_or([0, 0, 0]));
+            }
+        }
+        pixels
+    }
+
+    /// Write a binary PPM image
+    pub fn write_ppm(&self, mut writer: impl Write, scale: usize) -> io::Result<()> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.width * scale,
+            self.height * scale
+        )?;
+        writer.write_all(&self.pixels(scale))
+    }
+
+    pub fn write_png(&self, writer: impl Write, scale: usize) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(
+            writer,
+            (self.width * scale) as u32,
+            (self.height * scale) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()?
+            .write_image_data(&self.pixels(scale))
+    }
+}
+
+fn gradient(t: f64) -> Colour {
+    let t = t.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f64;
+    let i = (t as usize).min(GRADIENT.len() - 2);
+    let (from, to, t) = (GRADIENT[i], GRADIENT[i + 1], t - i as f64);
+    [0, 1, 2].map(|c| (from[c] as f64 + (to[c] as f64 - from[c] as f64) * t).round() as u8)
+}
+
+fn mix(colours: impl Iterator<Item = Colour>) -> Colour {
+    let (sum, count) = colours.fold(([0_usize; 3], 0), |(sum, count), colour| {
+        ([0, 1, 2].map(|c| sum[c] + colour[c] as usize), count + 1)
+    });
+    sum.map(|c| (c / count.max(1)) as u8)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let delivery = Delivery::new("^^>>vvvv<<<", 1);
+        let bounds = Bounds::of(&delivery);
+        assert_eq!(bounds.min, Location(-1, -2));
+        assert_eq!(bounds.max, Location(2, 2));
+        assert_eq!((bounds.width(), bounds.height()), (4, 5));
+
+        let heatmap = Heatmap::new(&delivery, Colouring::Visits);
+        assert_eq!(heatmap.origin, (1, 2));
+    }
+
+    #[test]
+    fn visit_colours() {
+        // The busiest house is the hottest, even when it only had 2 presents
+        let heatmap = Heatmap::new(&Delivery::new("^v^", 1), Colouring::Visits);
+        assert_eq!(heatmap.colour(0, 0), Some(gradient(1.0)));
+
+        let heatmap = Heatmap::new(&Delivery::new(">", 1), Colouring::Visits);
+        assert_eq!(heatmap.colour(1, 0), Some(gradient(0.0)));
+    }
+
+    #[test]
+    fn deliverer_colours() {
+        let heatmap = Heatmap::new(&Delivery::new("^>>^", 2), Colouring::Deliverer);
+        assert_eq!(heatmap.colour(0, 0), Some(PALETTE[0]));
+        assert_eq!(
+            heatmap.colour(1, 0),
+            Some(mix([PALETTE[0], PALETTE[1]].into_iter()))
+        );
+        assert_eq!(heatmap.colour(0, 1), Some(ORIGIN));
+        assert_eq!(heatmap.colour(1, 1), Some(PALETTE[1]));
+
+        let terminal = heatmap.to_terminal();
+        assert_eq!(terminal.lines().count(), 2);
+        assert!(terminal.lines().nth(1).unwrap().starts_with("\x1b[0m()"));
+    }
+
+    #[test]
+    fn images() {
+        let heatmap = Heatmap::new(&Delivery::new("^>v", 1), Colouring::Visits);
+
+        let mut ppm = Vec::new();
+        heatmap.write_ppm(&mut ppm, 3).unwrap();
+        assert!(ppm.starts_with(b"P6\n6 6\n255\n"));
+        assert_eq!(ppm.len(), 11 + 6 * 6 * 3);
+
+        let mut png = Vec::new();
+        heatmap.write_png(&mut png, 3).unwrap();
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (6, 6));
+        assert_eq!(pixels, heatmap.pixels(3));
+    }
+}
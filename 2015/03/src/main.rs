@@ -1,6 +1,11 @@
-use std::{collections::HashMap, fs};
+use std::{env, fs, io};
 
-#[derive(Eq, Hash, PartialEq, Clone, Copy)]
+use aoc_common::hash::FastMap;
+use heatmap::{Colouring, Heatmap};
+
+mod heatmap;
+
+#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug)]
 struct Location(isize, isize);
 
 impl Location {
@@ -27,27 +32,161 @@ impl Location {
     }
 }
 
+/// Where a group of deliverers went, with each of them taking turns to follow the next move
+struct Delivery {
+    deliverers: usize,
+    /// How many presents each deliverer dropped off at each house
+    houses: FastMap<Location, Vec<usize>>,
+}
+
+impl Delivery {
+    fn new(directions: &str, deliverers: usize) -> Self {
+        assert!(deliverers > 0, "Need at least one deliverer");
+
+        let mut houses: FastMap<Location, Vec<usize>> = FastMap::default();
+        // Everyone starts by delivering a present to the house at the origin
+        houses.insert(Location(0, 0), vec![1; deliverers]);
+
+        let mut locations = vec![Location(0, 0); deliverers];
+        for (i, dir) in directions
+            .chars()
+            .take_while(|dir| !dir.is_whitespace())
+            .enumerate()
+        {
+            let deliverer = i % deliverers;
+            locations[deliverer] = locations[deliverer].travel(dir);
+            houses
+                .entry(locations[deliverer])
+                .or_insert_with(|| vec![0; deliverers])[deliverer] += 1;
+        }
+
+        Self { deliverers, houses }
+    }
+
+    /// Number of houses that got at least one present
+    fn unique_houses(&self) -> usize {
+        self.houses.len()
+    }
+
+    /// Number of different houses each deliverer visited
+    fn houses_per_deliverer(&self) -> Vec<usize> {
+        (0..self.deliverers)
+            .map(|deliverer| {
+                self.houses
+                    .values()
+                    .filter(|visits| visits[deliverer] > 0)
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Number of houses visited by more than one deliverer
+    fn shared_houses(&self) -> usize {
+        self.houses
+            .values()
+            .filter(|visits| visits.iter().filter(|&&count| count > 0).count() > 1)
+            .count()
+    }
+}
+
 fn main() {
     let input = fs::read_to_string("input.txt").unwrap();
-    let (robot, santa): (Vec<_>, Vec<_>) = input
-        .chars()
-        .take_while(|dir| !dir.is_whitespace())
-        .enumerate()
-        .partition(|&(i, _)| i % 2 == 0);
-    let mut map: HashMap<Location, usize> = HashMap::new();
-
-    let mut current_location = Location(0, 0);
-    map.insert(current_location, 1);
-    for dir in robot.iter().map(|(_, dir)| dir) {
-        current_location = current_location.travel(*dir);
-        let count = map.entry(current_location).or_insert(0);
-        *count += 1;
-    }
-    current_location = Location(0, 0);
-    for dir in santa.iter().map(|(_, dir)| dir) {
-        current_location = current_location.travel(*dir);
-        let count = map.entry(current_location).or_insert(0);
-        *count += 1;
-    }
-    println!("Houses: {:#?}", map.values().len());
+
+    println!("Houses: {}", Delivery::new(&input, 1).unique_houses());
+
+    let delivery = Delivery::new(&input, 2);
+    println!("Houses with Robo-Santa: {}", delivery.unique_houses());
+    println!(
+        "Houses per deliverer: {:?}",
+        delivery.houses_per_deliverer()
+    );
+    println!("Shared houses: {}", delivery.shared_houses());
+
+    draw_heatmap(&input);
+}
+
+/// Draw the delivery if any of `--heatmap`, `--ppm <file>` or `--png <file>` are passed,
+/// split between `--deliverers <n>` (2 by default) and coloured `--by-deliverer` or by visits
+fn draw_heatmap(input: &str) {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let flag_value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+
+    let outputs = ["--heatmap", "--ppm", "--png"];
+    if !args.iter().any(|arg| outputs.contains(&arg.as_str())) {
+        return;
+    }
+
+    let deliverers = flag_value("--deliverers").map_or(2, |n| n.parse().unwrap());
+    let scale = flag_value("--scale").map_or(4, |n| n.parse().unwrap());
+    let colouring = if args.iter().any(|arg| arg == "--by-deliverer") {
+        Colouring::Deliverer
+    } else {
+        Colouring::Visits
+    };
+    let heatmap = Heatmap::new(&Delivery::new(input, deliverers), colouring);
+
+    if args.iter().any(|arg| arg == "--heatmap") {
+        print!("{}", heatmap.to_terminal());
+    }
+    if let Some(path) = flag_value("--ppm") {
+        let file = fs::File::create(path).expect("Failed to create image");
+        heatmap
+            .write_ppm(io::BufWriter::new(file), scale)
+            .expect("Failed to write image");
+    }
+    if let Some(path) = flag_value("--png") {
+        let file = fs::File::create(path).expect("Failed to create image");
+        heatmap
+            .write_png(io::BufWriter::new(file), scale)
+            .expect("Failed to write image");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use aoc_common::bench;
+
+    use crate::*;
+
+    #[test]
+    fn part_1() {
+        assert_eq!(Delivery::new(">", 1).unique_houses(), 2);
+        assert_eq!(Delivery::new("^>v<", 1).unique_houses(), 4);
+        assert_eq!(Delivery::new("^v^v^v^v^v", 1).unique_houses(), 2);
+    }
+
+    #[test]
+    fn part_2() {
+        assert_eq!(Delivery::new("^v", 2).unique_houses(), 3);
+        assert_eq!(Delivery::new("^>v<", 2).unique_houses(), 3);
+        assert_eq!(Delivery::new("^v^v^v^v^v", 2).unique_houses(), 11);
+    }
+
+    #[test]
+    fn deliverers() {
+        let delivery = Delivery::new("^>v<\n", 2);
+        assert_eq!(delivery.houses_per_deliverer(), vec![2, 2]);
+        assert_eq!(delivery.shared_houses(), 1);
+
+        let delivery = Delivery::new("^>^<", 3);
+        assert_eq!(delivery.unique_houses(), 4);
+        assert_eq!(delivery.houses_per_deliverer(), vec![3, 2, 2]);
+        assert_eq!(delivery.shared_houses(), 2);
+    }
+
+    #[test]
+    #[ignore]
+    fn timing() {
+        let input = bench::input("^>v<^^>>vv<<^v^v^v^v^v>>^^<<vv");
+        bench::time("2015/03 both parts", || {
+            (
+                Delivery::new(&input, 1).unique_houses(),
+                Delivery::new(&input, 2).unique_houses(),
+            )
+        });
+    }
 }
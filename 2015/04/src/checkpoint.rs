@@ -0,0 +1,88 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A file recording how far each search has got, as lines of
+/// `secret key<TAB>target<TAB>highest nonce checked`
+pub struct Checkpoint {
+    path: PathBuf,
+}
+
+impl Checkpoint {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, String, usize)> {
+        let Ok(contents) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                Some((
+                    fields.next()?.to_owned(),
+                    fields.next()?.to_owned(),
+                    fields.next()?.parse().ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// The highest nonce already checked for this search, if it has been run before
+    pub fn load(&self, secret_key: &str, target: &str) -> Option<usize> {
+        self.entries()
+            .into_iter()
+            .find(|(key, spec, _)| key == secret_key && spec == target)
+            .map(|(_, _, checked)| checked)
+    }
+
+    /// Record that every nonce up to `checked` has been tried for this search
+    pub fn save(&self, secret_key: &str, target: &str, checked: usize) -> io::Result<()> {
+        let mut entries = self.entries();
+        match entries
+            .iter_mut()
+            .find(|(key, spec, _)| key == secret_key && spec == target)
+        {
+            Some(entry) => entry.2 = entry.2.max(checked),
+            None => entries.push((secret_key.to_owned(), target.to_owned(), checked)),
+        }
+
+        let contents: String = entries
+            .iter()
+            .map(|(key, spec, checked)| format!("{key}\t{spec}\t{checked}\n"))
+            .collect();
+
+        // Write to a temporary file first so a crash can't leave a half written checkpoint
+        let temporary = self.path.with_extension("tmp");
+        fs::write(&temporary, contents)?;
+        fs::rename(temporary, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("aoc_2015_04_{}.tsv", std::process::id()));
+        let checkpoint = Checkpoint::new(&path);
+        assert_eq!(checkpoint.load("abcdef", "00000"), None);
+
+        checkpoint.save("abcdef", "00000", 1000).unwrap();
+        checkpoint.save("abcdef", "000000", 5000).unwrap();
+        checkpoint.save("abcdef", "00000", 2000).unwrap();
+        checkpoint.save("abcdef", "00000", 1500).unwrap();
+
+        assert_eq!(checkpoint.load("abcdef", "00000"), Some(2000));
+        assert_eq!(checkpoint.load("abcdef", "000000"), Some(5000));
+        assert_eq!(checkpoint.load("pqrstuv", "00000"), None);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,20 +1,380 @@
+use std::{
+    collections::BTreeSet,
+    env, fmt, fs,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use checkpoint::Checkpoint;
+
+mod checkpoint;
+
+/// How many nonces a worker claims at a time
+const BATCH_SIZE: usize = 10_000;
+
+/// How often to save progress and report the hash rate
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What a hash has to look like to mine an AdventCoin
+#[derive(PartialEq, Debug)]
+enum Target {
+    /// The hash must start with these hex digits
+    Prefix(Vec<u8>),
+    /// The hash, read as a big-endian number, must be below this
+    Below([u8; 16]),
+}
+
+impl Target {
+    fn zeroes(number_of_zeroes: usize) -> Self {
+        Self::Prefix(vec![0; number_of_zeroes])
+    }
+
+    /// Checks the raw digest, rather than formatting it to hex first
+    fn accepts(&self, digest: &md5::Digest) -> bool {
+        match self {
+            Self::Prefix(nibbles) => nibbles.iter().enumerate().all(|(i, &nibble)| {
+                let byte = digest[i / 2];
+                nibble == if i % 2 == 0 { byte >> 4 } else { byte & 0xf }
+            }),
+            Self::Below(threshold) => digest.0 < *threshold,
+        }
+    }
+
+    /// The target written so it can be parsed again
+    fn spec(&self) -> String {
+        match self {
+            Self::Prefix(nibbles) => nibbles.iter().map(|nibble| format!("{nibble:x}")).collect(),
+            Self::Below(threshold) => format!("<{:x}", md5::Digest(*threshold)),
+        }
+    }
+
+    /// How many hashes it takes to find a match on average
+    ///
+    /// Every hash is equally likely to match, so this is also how much work is left
+    /// no matter how long the search has been going.
+    fn expected_hashes(&self) -> f64 {
+        match self {
+            Self::Prefix(nibbles) => 16_f64.powi(nibbles.len() as i32),
+            Self::Below(threshold) => {
+                let threshold = threshold
+                    .iter()
+                    .fold(0.0, |total, &byte| total * 256.0 + byte as f64);
+                2_f64.powi(128) / threshold
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for Target {
+    type Error = &'static str;
+
+    /// A hex prefix like `000000`, or a threshold like `<00000f`, where missing
+    /// trailing digits of the threshold are zero
+    fn try_from(target: &str) -> Result<Self, Self::Error> {
+        let (below, hex) = match target.strip_prefix('<') {
+            Some(hex) => (true, hex),
+            None => (false, target),
+        };
+        let nibbles = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or("Target must be made of hex digits")?;
+        if nibbles.is_empty() {
+            return Err("Target is empty");
+        }
+        if nibbles.len() > 32 {
+            return Err("Target is longer than an MD5 hash");
+        }
+
+        if below {
+            let mut threshold = [0; 16];
+            for (i, nibble) in nibbles.iter().enumerate() {
+                threshold[i / 2] |= if i % 2 == 0 { nibble << 4 } else { *nibble };
+            }
+            // No hash is below zero, so mining would never finish
+            if threshold == [0; 16] {
+                return Err("Threshold must be above zero");
+            }
+            Ok(Self::Below(threshold))
+        } else {
+            Ok(Self::Prefix(nibbles))
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Prefix(nibbles) if nibbles.iter().all(|&nibble| nibble == 0) => {
+                write!(f, "{} zeroes", nibbles.len())
+            }
+            Self::Prefix(nibbles) => {
+                write!(f, "prefix ")?;
+                nibbles
+                    .iter()
+                    .try_for_each(|nibble| write!(f, "{nibble:x}"))
+            }
+            Self::Below(threshold) => write!(f, "below {:x}", md5::Digest(*threshold)),
+        }
+    }
+}
+
+/// Usage: `aoc_2015_04 [secret key] [target] [--checkpoint <file>]`, where the secret
+/// key defaults to the contents of input.txt, both parts of the puzzle are solved if
+/// there's no target, and progress is saved to and resumed from the checkpoint file
 fn main() {
-    let answer = calculate_answer("ckczppom", 5);
-    println!("The answer is {answer}.");
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let checkpoint = args.iter().position(|arg| arg == "--checkpoint").map(|i| {
+        args.remove(i);
+        Checkpoint::new(args.remove(i))
+    });
+
+    let secret_key = match args.first() {
+        Some(secret_key) => secret_key.clone(),
+        None => fs::read_to_string("input.txt")
+            .expect("Failed to read input file")
+            .trim()
+            .to_owned(),
+    };
+
+    let targets = match args.get(1) {
+        Some(target) => vec![Target::try_from(target.as_str()).unwrap()],
+        None => vec![Target::zeroes(5), Target::zeroes(6)],
+    };
+    for target in targets {
+        let answer = calculate_answer(&secret_key, &target, checkpoint.as_ref());
+        println!("The answer is {answer}.");
+    }
 }
 
-fn calculate_answer(secret_key: &str, number_of_zeroes: usize) -> usize {
-    println!("Calculating for {number_of_zeroes} zeroes...");
+fn calculate_answer(secret_key: &str, target: &Target, checkpoint: Option<&Checkpoint>) -> usize {
+    println!("Calculating for {target}...");
 
-    let mut answer: usize = 1;
+    let spec = target.spec();
+    let save = |checked| {
+        if let Some(checkpoint) = checkpoint {
+            checkpoint
+                .save(secret_key, &spec, checked)
+                .expect("Failed to save checkpoint");
+        }
+    };
+    let resumed = checkpoint
+        .and_then(|checkpoint| checkpoint.load(secret_key, &spec))
+        .unwrap_or(0);
+    if resumed > 0 {
+        println!("Resuming after nonce {resumed}");
+    }
+
+    let started = Instant::now();
+    let last_report = Mutex::new(started);
+    let progress = |checked: usize| {
+        let mut last_report = last_report.lock().unwrap();
+        if last_report.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        *last_report = Instant::now();
+        save(checked);
+
+        let rate = (checked - resumed) as f64 / started.elapsed().as_secs_f64();
+        println!(
+            "Checked up to {checked} at {:.2} MH/s, expecting a match within ~{:.0}s",
+            rate / 1e6,
+            target.expected_hashes() / rate
+        );
+    };
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let answer = mine(
+        secret_key,
+        |digest| target.accepts(digest),
+        threads,
+        BATCH_SIZE,
+        resumed + 1,
+        progress,
+    );
+    // Resuming a finished search will find the answer straight away
+    save(answer - 1);
+
+    let hash = md5::compute(format!("{secret_key}{answer}"));
+    println!("Found hash that matches requirement: {hash:x}");
+    answer
+}
+
+/// Find the lowest nonce from `first` onwards whose hash with the secret key is accepted
+///
+/// Workers claim batches of nonces in increasing order, so once a valid nonce is
+/// found, any batch starting after it can be skipped, and the search is over when
+/// every batch starting before it has been finished.
+///
+/// `progress` is called with the highest nonce that it and every nonce before it have
+/// been checked, each time that goes up.
+fn mine(
+    secret_key: &str,
+    accept: impl Fn(&md5::Digest) -> bool + Sync,
+    threads: usize,
+    batch_size: usize,
+    first: usize,
+    progress: impl Fn(usize) + Sync,
+) -> usize {
+    let next_batch = AtomicUsize::new(first);
+    let best = AtomicUsize::new(usize::MAX);
+    // The first nonce that hasn't been checked, and batches that have been finished
+    // beyond it, which can finish out of order
+    let finished = Mutex::new((first, BTreeSet::new()));
 
-    let zeroes = "0".repeat(number_of_zeroes);
+    // Every hash starts with the secret key, so only hash it once
+    let mut prefix = md5::Context::new();
+    prefix.consume(secret_key);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut buffer = [0; 20];
+                loop {
+                    let start = next_batch.fetch_add(batch_size, Ordering::Relaxed);
+                    if start >= best.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let found = (start..start + batch_size).find(|&nonce| {
+                        let mut context = prefix.clone();
+                        context.consume(digits(nonce, &mut buffer));
+                        accept(&context.compute())
+                    });
+                    if let Some(nonce) = found {
+                        best.fetch_min(nonce, Ordering::Relaxed);
+                        continue;
+                    }
+
+                    let mut finished = finished.lock().unwrap();
+                    let (unchecked, batches) = &mut *finished;
+                    batches.insert(start);
+                    let before = *unchecked;
+                    while batches.remove(unchecked) {
+                        *unchecked += batch_size;
+                    }
+                    // Batches that finish early don't move it until the ones before do
+                    if *unchecked > before {
+                        progress(*unchecked - 1);
+                    }
+                }
+            });
+        }
+    });
+
+    best.into_inner()
+}
+
+/// Write a number in decimal without allocating
+fn digits(mut number: usize, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut start = buffer.len();
     loop {
-        let hash = format!("{:x}", md5::compute(format!("{secret_key}{answer}")));
-        if hash[0..number_of_zeroes] == zeroes {
-            println!("Found hash that matches requirement: {hash}");
-            return answer;
+        start -= 1;
+        buffer[start] = b'0' + (number % 10) as u8;
+        number /= 10;
+        if number == 0 {
+            return &buffer[start..];
         }
-        answer += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn examples() {
+        assert_eq!(calculate_answer("abcdef", &Target::zeroes(5), None), 609043);
+        assert_eq!(
+            calculate_answer("pqrstuv", &Target::zeroes(5), None),
+            1048970
+        );
+    }
+
+    #[test]
+    fn lowest_nonce() {
+        // Plenty of nonces are accepted, but the lowest must win however the work is split
+        let accept = |digest: &md5::Digest| digest[0] < 8;
+        let expected = (1..)
+            .find(|nonce| accept(&md5::compute(format!("abc{nonce}"))))
+            .unwrap();
+        for (threads, batch_size) in [(1, 1), (4, 1), (3, 7), (8, 100)] {
+            assert_eq!(
+                mine("abc", accept, threads, batch_size, 1, |_| ()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn resume() {
+        let accept = |digest: &md5::Digest| digest[0] < 8;
+        let first = mine("abc", accept, 1, 10, 1, |_| ());
+        let second = mine("abc", accept, 4, 10, first + 1, |_| ());
+        assert!(second > first);
+        assert!(accept(&md5::compute(format!("abc{second}"))));
+
+        // Progress only ever goes up, and never past a nonce that hasn't been checked
+        let checked = Mutex::new(Vec::new());
+        mine("abc", accept, 4, 3, first + 1, |nonce| {
+            checked.lock().unwrap().push(nonce)
+        });
+        let checked = checked.into_inner().unwrap();
+        assert!(checked.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(checked.iter().all(|&nonce| nonce > first && nonce < second));
+    }
+
+    #[test]
+    fn targets() {
+        // 000001dbbfa3a5c83a2d506429c7b00e
+        let digest = md5::compute("abcdef609043");
+
+        assert!(Target::zeroes(5).accepts(&digest));
+        assert!(!Target::zeroes(6).accepts(&digest));
+        assert!(Target::try_from("000001d").unwrap().accepts(&digest));
+        assert!(!Target::try_from("000001e").unwrap().accepts(&digest));
+        assert!(Target::try_from("<000001dc").unwrap().accepts(&digest));
+        assert!(!Target::try_from("<000001db").unwrap().accepts(&digest));
+
+        assert_eq!(
+            Target::try_from("<0f"),
+            Ok(Target::Below([
+                0x0f, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+            ]))
+        );
+        assert!(Target::try_from("00g").is_err());
+        assert_eq!(Target::try_from(""), Err("Target is empty"));
+        assert_eq!(Target::try_from("<"), Err("Target is empty"));
+        assert_eq!(
+            Target::try_from("<000"),
+            Err("Threshold must be above zero")
+        );
+        assert!(Target::try_from(&*"0".repeat(33)).is_err());
+        assert_eq!(Target::try_from("00000").unwrap().to_string(), "5 zeroes");
+        assert_eq!(
+            Target::try_from("c0ffee").unwrap().to_string(),
+            "prefix c0ffee"
+        );
+
+        for spec in ["000000", "c0ffee", "<00000f00000000000000000000000000"] {
+            assert_eq!(Target::try_from(spec).unwrap().spec(), spec);
+        }
+        assert_eq!(Target::zeroes(5).expected_hashes(), 1048576.0);
+        assert_eq!(Target::try_from("<01").unwrap().expected_hashes(), 256.0);
+    }
+
+    #[test]
+    fn decimal() {
+        let mut buffer = [0; 20];
+        assert_eq!(digits(0, &mut buffer), b"0");
+        assert_eq!(digits(609043, &mut buffer), b"609043");
+        assert_eq!(
+            digits(usize::MAX, &mut buffer),
+            usize::MAX.to_string().as_bytes()
+        );
     }
 }
@@ -1,58 +1,132 @@
-use itertools::Itertools;
+use std::{
+    cell::RefCell,
+    io::{self, BufRead},
+};
 
-const NAUGHTY_COMBOS: [&str; 4] = ["ab", "cd", "pq", "xy"];
-const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+pub mod rules;
 
-pub fn nice(word: &str) -> bool {
-    // Doesn't contain any naughty combos
-    if NAUGHTY_COMBOS.iter().any(|combo| word.contains(combo)) {
-        return false;
+/// How many distinct pairs of bytes there are
+const PAIRS: usize = 1 << 16;
+
+/// Whether a word is nice under each set of rules
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Classification {
+    pub nice: bool,
+    pub new_nice: bool,
+}
+
+/// Checks a word against both sets of rules in a single pass over its bytes
+pub struct Scanner {
+    /// For each pair of bytes, the word it was last seen in and where it was first
+    /// seen in that word, so the table never needs clearing between words
+    pairs: Vec<(u32, u32)>,
+    word: u32,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Self {
+            pairs: vec![(0, 0); PAIRS],
+            word: 0,
+        }
     }
+}
 
-    // Has at least 3 vowels
-    if word
-        .chars()
-        .into_iter()
-        .filter(|letter| VOWELS.contains(letter))
-        .count()
-        < 3
-    {
-        return false;
+impl Scanner {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    // Has at least one double letter
-    if !word
-        .chars()
-        .into_iter()
-        .enumerate()
-        .any(|(i, letter)| letter == word.chars().nth(i + 1).unwrap_or('!'))
-    {
-        return false;
+    pub fn classify(&mut self, word: &[u8]) -> Classification {
+        self.word = self.word.wrapping_add(1);
+        if self.word == 0 {
+            self.pairs.fill((0, 0));
+            self.word = 1;
+        }
+
+        let mut vowels = 0;
+        let mut double_letter = false;
+        let mut naughty_combo = false;
+        let mut repeated_pair = false;
+        let mut sandwich = false;
+
+        for (i, &letter) in word.iter().enumerate() {
+            if matches!(letter, b'a' | b'e' | b'i' | b'o' | b'u') {
+                vowels += 1;
+            }
+            if i >= 2 && word[i - 2] == letter {
+                sandwich = true;
+            }
+            if i == 0 {
+                continue;
+            }
+
+            let previous = word[i - 1];
+            double_letter |= previous == letter;
+            naughty_combo |= matches!(
+                (previous, letter),
+                (b'a', b'b') | (b'c', b'd') | (b'p', b'q') | (b'x', b'y')
+            );
+
+            let start = i as u32 - 1;
+            let seen = &mut self.pairs[(previous as usize) << 8 | letter as usize];
+            if seen.0 != self.word {
+                *seen = (self.word, start);
+            } else if seen.1 + 2 <= start {
+                // Far enough along from where it was first seen that they don't overlap
+                repeated_pair = true;
+            }
+        }
+
+        Classification {
+            nice: !naughty_combo && vowels >= 3 && double_letter,
+            new_nice: repeated_pair && sandwich,
+        }
     }
 
-    true
+    /// Classify every line of a stream, without needing the whole thing in memory
+    pub fn classify_stream(&mut self, mut reader: impl BufRead) -> io::Result<Tally> {
+        let mut tally = Tally::default();
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            tally.bytes += line.len();
+            let word = line.trim_ascii_end();
+            if !word.is_empty() {
+                let classification = self.classify(word);
+                tally.words += 1;
+                tally.nice += classification.nice as usize;
+                tally.new_nice += classification.new_nice as usize;
+            }
+            line.clear();
+        }
+        Ok(tally)
+    }
 }
 
-pub fn new_nice(word: &str) -> bool {
-    let counts: Vec<(char, usize)> = word
-        .chars()
-        .map(|letter| (letter, 1))
-        .coalesce(|(l1, c1), (l2, c2)| {
-            if l1 == l2 {
-                Ok((l1, c1 + c2))
-            } else {
-                Err(((l1, c1), (l2, c2)))
-            }
-        })
-        .collect();
-    println!("{:?}", counts);
+/// Counts of the words in a stream
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Tally {
+    pub bytes: usize,
+    pub words: usize,
+    pub nice: usize,
+    pub new_nice: usize,
+}
+
+thread_local! {
+    static SCANNER: RefCell<Scanner> = RefCell::new(Scanner::new());
+}
+
+pub fn nice(word: &str) -> bool {
+    SCANNER.with(|scanner| scanner.borrow_mut().classify(word.as_bytes()).nice)
+}
 
-    true
+pub fn new_nice(word: &str) -> bool {
+    SCANNER.with(|scanner| scanner.borrow_mut().classify(word.as_bytes()).new_nice)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{new_nice, nice};
+    use crate::*;
 
     #[test]
     fn nice_words() {
@@ -70,5 +144,57 @@ mod tests {
     #[test]
     fn new_nice_words() {
         assert!(new_nice("aabbbaabbc"));
+        assert!(new_nice("qjhvhtzxzqqjkmpb"));
+        assert!(new_nice("xxyxx"));
+    }
+
+    #[test]
+    fn new_naughty_words() {
+        assert!(!new_nice("uurcxstgmygtbstg"));
+        assert!(!new_nice("ieodomkazucvgmuy"));
+        // The pair can't overlap itself
+        assert!(!new_nice("aaa"));
+    }
+
+    #[test]
+    fn matches_rules() {
+        let (original, updated) = (rules::Ruleset::original(), rules::Ruleset::updated());
+        let mut scanner = Scanner::new();
+
+        // Short words from a small alphabet hit every rule plenty of times
+        let mut state: u64 = 5;
+        for _ in 0..10_000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let length = (state >> 60) as usize + 1;
+            let word: String = (0..length)
+                .map(|i| b"abcdepqxy"[(state >> (i * 3 % 56)) as usize % 9] as char)
+                .collect();
+
+            let classification = scanner.classify(word.as_bytes());
+            assert_eq!(
+                classification.nice,
+                original.evaluate(&word).is_nice(),
+                "{word}"
+            );
+            assert_eq!(
+                classification.new_nice,
+                updated.evaluate(&word).is_nice(),
+                "{word}"
+            );
+        }
+    }
+
+    #[test]
+    fn stream() {
+        let list = "ugknbfddgicrmopn\r\njchzalrnumimnmhp\n\nqjhvhtzxzqqjkmpb\nxxyxx";
+        assert_eq!(
+            Scanner::new().classify_stream(list.as_bytes()).unwrap(),
+            Tally {
+                bytes: list.len(),
+                words: 4,
+                nice: 1,
+                new_nice: 2,
+            }
+        );
     }
 }
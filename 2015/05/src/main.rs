@@ -1,14 +1,63 @@
-use std::fs;
+use std::{env, fs, io, time::Instant};
 
-use aoc_2015_05::*;
+use aoc_2015_05::{rules::Ruleset, *};
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // Classify a huge list of words as fast as possible with `--bulk <file>`, or `-` for stdin
+    if let Some(i) = args.iter().position(|arg| arg == "--bulk") {
+        bulk(args.get(i + 1).expect("Missing file to classify"));
+        return;
+    }
+
     let strings = fs::read_to_string("input.txt").expect("Failed to read input");
-    let nice_count: usize = strings
+    let words: Vec<&str> = strings
         .lines()
         .take_while(|line| !line.is_empty())
-        .map(nice)
-        .filter(|nice| *nice)
-        .count();
-    println!("There are {} nice words in Santa's list", nice_count);
+        .collect();
+
+    let mut scanner = Scanner::new();
+    let classifications: Vec<Classification> = words
+        .iter()
+        .map(|word| scanner.classify(word.as_bytes()))
+        .collect();
+
+    let nice_count = classifications.iter().filter(|c| c.nice).count();
+    println!("There are {nice_count} nice words in Santa's list");
+
+    let new_nice_count = classifications.iter().filter(|c| c.new_nice).count();
+    println!("There are {new_nice_count} nice words with the new rules");
+
+    // Explain why each word is nice or naughty with `--explain`
+    if args.iter().any(|arg| arg == "--explain") {
+        for ruleset in [Ruleset::original(), Ruleset::updated()] {
+            println!("\nWith the {} rules:", ruleset.name);
+            for word in &words {
+                println!("{word}: {}", ruleset.evaluate(word));
+            }
+        }
+    }
+}
+
+fn bulk(path: &str) {
+    let started = Instant::now();
+    let mut scanner = Scanner::new();
+    let tally = if path == "-" {
+        scanner.classify_stream(io::stdin().lock())
+    } else {
+        let file = fs::File::open(path).expect("Failed to open file");
+        scanner.classify_stream(io::BufReader::with_capacity(1 << 20, file))
+    }
+    .expect("Failed to read words");
+    let seconds = started.elapsed().as_secs_f64();
+
+    println!(
+        "Classified {} words in {seconds:.2}s ({:.1}M words/s, {:.1} MB/s)",
+        tally.words,
+        tally.words as f64 / seconds / 1e6,
+        tally.bytes as f64 / seconds / 1e6
+    );
+    println!("{} nice with the original rules", tally.nice);
+    println!("{} nice with the new rules", tally.new_nice);
 }
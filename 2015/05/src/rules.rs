@@ -0,0 +1,242 @@
+use std::fmt;
+
+/// Something a word has to satisfy to be nice
+pub trait Rule {
+    /// What the rule asks for, used when explaining why a word is naughty
+    fn description(&self) -> String;
+
+    /// `Err` with the positions in the word that broke the rule, or that came closest to
+    /// meeting it, which can be empty if there's nothing in particular to point at
+    fn check(&self, word: &str) -> Result<(), Vec<usize>>;
+}
+
+/// The word can't contain any of these substrings
+pub struct Forbidden(pub &'static [&'static str]);
+
+impl Rule for Forbidden {
+    fn description(&self) -> String {
+        format!("doesn't contain {}", self.0.join(", "))
+    }
+
+    fn check(&self, word: &str) -> Result<(), Vec<usize>> {
+        let mut positions: Vec<usize> = self
+            .0
+            .iter()
+            .flat_map(|forbidden| word.match_indices(forbidden).map(|(i, _)| i))
+            .collect();
+        if positions.is_empty() {
+            return Ok(());
+        }
+        positions.sort_unstable();
+        Err(positions)
+    }
+}
+
+/// The word needs at least this many vowels
+pub struct MinVowels(pub usize);
+
+impl Rule for MinVowels {
+    fn description(&self) -> String {
+        format!("has at least {} vowels", self.0)
+    }
+
+    fn check(&self, word: &str) -> Result<(), Vec<usize>> {
+        let vowels: Vec<usize> = word
+            .char_indices()
+            .filter(|(_, letter)| "aeiou".contains(*letter))
+            .map(|(i, _)| i)
+            .collect();
+        if vowels.len() >= self.0 {
+            Ok(())
+        } else {
+            Err(vowels)
+        }
+    }
+}
+
+/// The word has a letter twice in a row, like `dd`
+pub struct DoubleLetter;
+
+impl Rule for DoubleLetter {
+    fn description(&self) -> String {
+        String::from("has a letter twice in a row")
+    }
+
+    fn check(&self, word: &str) -> Result<(), Vec<usize>> {
+        if word.as_bytes().windows(2).any(|pair| pair[0] == pair[1]) {
+            Ok(())
+        } else {
+            Err(Vec::new())
+        }
+    }
+}
+
+/// The word has a pair of letters that appears twice without overlapping, like `xyxy`
+pub struct RepeatingPair;
+
+impl Rule for RepeatingPair {
+    fn description(&self) -> String {
+        String::from("has a pair of letters twice without overlapping")
+    }
+
+    fn check(&self, word: &str) -> Result<(), Vec<usize>> {
+        let letters = word.as_bytes();
+        if letters
+            .windows(2)
+            .enumerate()
+            .any(|(i, pair)| letters[i + 2..].windows(2).any(|other| other == pair))
+        {
+            Ok(())
+        } else {
+            Err(Vec::new())
+        }
+    }
+}
+
+/// The word has a letter that repeats with exactly one letter between them, like `aba`
+pub struct LetterSandwich;
+
+impl Rule for LetterSandwich {
+    fn description(&self) -> String {
+        String::from("has a letter repeated with one letter between")
+    }
+
+    fn check(&self, word: &str) -> Result<(), Vec<usize>> {
+        if word.as_bytes().windows(3).any(|three| three[0] == three[2]) {
+            Ok(())
+        } else {
+            Err(Vec::new())
+        }
+    }
+}
+
+/// A named set of rules that a word has to satisfy all of to be nice
+pub struct Ruleset {
+    pub name: &'static str,
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Ruleset {
+    pub fn new(name: &'static str, rules: Vec<Box<dyn Rule>>) -> Self {
+        Self { name, rules }
+    }
+
+    /// The rules from part 1
+    pub fn original() -> Self {
+        Self::new(
+            "original",
+            vec![
+                Box::new(Forbidden(&["ab", "cd", "pq", "xy"])),
+                Box::new(MinVowels(3)),
+                Box::new(DoubleLetter),
+            ],
+        )
+    }
+
+    /// The rules from part 2
+    pub fn updated() -> Self {
+        Self::new(
+            "updated",
+            vec![Box::new(RepeatingPair), Box::new(LetterSandwich)],
+        )
+    }
+
+    pub fn evaluate(&self, word: &str) -> Verdict {
+        Verdict {
+            failures: self
+                .rules
+                .iter()
+                .filter_map(|rule| {
+                    rule.check(word).err().map(|positions| Failure {
+                        rule: rule.description(),
+                        positions,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A rule a word didn't satisfy
+#[derive(PartialEq, Debug)]
+pub struct Failure {
+    pub rule: String,
+    pub positions: Vec<usize>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Verdict {
+    pub failures: Vec<Failure>,
+}
+
+impl Verdict {
+    pub fn is_nice(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_nice() {
+            return write!(f, "nice");
+        }
+        write!(f, "naughty")?;
+        for failure in &self.failures {
+            write!(f, "\n  - fails \"{}\"", failure.rule)?;
+            if !failure.positions.is_empty() {
+                write!(f, " (positions {:?})", failure.positions)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::*;
+    use crate::{new_nice, nice};
+
+    #[test]
+    fn matches_functions() {
+        let (original, updated) = (Ruleset::original(), Ruleset::updated());
+        for word in [
+            "ugknbfddgicrmopn",
+            "aaa",
+            "jchzalrnumimnmhp",
+            "haegwjzuvuyypxyu",
+            "dvszwmarrgswjxmb",
+            "qjhvhtzxzqqjkmpb",
+            "xxyxx",
+            "uurcxstgmygtbstg",
+            "ieodomkazucvgmuy",
+        ] {
+            assert_eq!(original.evaluate(word).is_nice(), nice(word), "{word}");
+            assert_eq!(updated.evaluate(word).is_nice(), new_nice(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn explanations() {
+        let verdict = Ruleset::original().evaluate("haegwjzuvuyypxyu");
+        assert_eq!(
+            verdict.failures,
+            vec![Failure {
+                rule: String::from("doesn't contain ab, cd, pq, xy"),
+                positions: vec![13],
+            }]
+        );
+
+        let verdict = Ruleset::original().evaluate("dvszwmarrgswjxmb");
+        assert_eq!(
+            verdict.to_string(),
+            "naughty\n  - fails \"has at least 3 vowels\" (positions [6])"
+        );
+
+        let verdict = Ruleset::updated().evaluate("ieodomkazucvgmuy");
+        assert_eq!(verdict.failures.len(), 1);
+        assert_eq!(
+            verdict.failures[0].rule,
+            "has a pair of letters twice without overlapping"
+        );
+    }
+}
@@ -1,23 +1,133 @@
-use std::fs;
+use std::{cmp::Reverse, collections::BinaryHeap, fs};
 
-fn main() {
-    let input = fs::read_to_string("input.txt").unwrap();
+use aoc_common::parse::sections;
+
+#[derive(Debug, PartialEq)]
+struct Elf {
+    /// Position of the elf in the list, starting from 1
+    index: usize,
+    items: Vec<usize>,
+}
+
+impl Elf {
+    fn calories(&self) -> usize {
+        self.items.iter().sum()
+    }
+}
+
+struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl TryFrom<&str> for Inventory {
+    type Error = String;
+
+    fn try_from(list: &str) -> Result<Self, Self::Error> {
+        let elves = sections(list)
+            .into_iter()
+            .enumerate()
+            .map(|(i, bag)| {
+                let items = bag
+                    .iter()
+                    .map(|item| {
+                        item.trim()
+                            .parse()
+                            .map_err(|_| format!("Elf {} has an invalid item {item:?}", i + 1))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Elf {
+                    index: i + 1,
+                    items,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { elves })
+    }
+}
 
-    let mut bags: Vec<usize> = Vec::new();
-    let mut subtotal: usize = 0;
-    for line in input.lines() {
-        if let Ok(value) = line.parse::<usize>() {
-            subtotal += value;
-        } else {
-            bags.push(subtotal);
-            subtotal = 0;
+impl Inventory {
+    /// The `n` elves carrying the most calories, most first, with ties going to the
+    /// elf earlier in the list
+    ///
+    /// Only keeps `n` elves around at a time, rather than sorting all of them
+    fn top(&self, n: usize) -> Vec<&Elf> {
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for elf in &self.elves {
+            // A min-heap of the best so far, so the worst of them can be dropped
+            heap.push(Reverse((elf.calories(), Reverse(elf.index))));
+            if heap.len() > n {
+                heap.pop();
+            }
         }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(index)))| &self.elves[index - 1])
+            .collect()
     }
+}
+
+fn main() {
+    let input = fs::read_to_string("input.txt").unwrap();
+    let inventory = Inventory::try_from(input.as_str()).unwrap();
+
+    let Some(largest) = inventory.top(1).first().copied() else {
+        println!("There are no elves");
+        return;
+    };
+    println!(
+        "The largest bag contains: {} calories (elf {})",
+        largest.calories(),
+        largest.index
+    );
+
+    let largest3 = inventory.top(3);
+    println!(
+        "The largest 3 bags have: {} calories (elves {:?})",
+        largest3.iter().map(|elf| elf.calories()).sum::<usize>(),
+        largest3.iter().map(|elf| elf.index).collect::<Vec<_>>()
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
 
-    bags.sort_unstable_by(|a, b| b.cmp(a));
+    #[test]
+    fn top_elves() {
+        let inventory = Inventory::try_from(EXAMPLE).unwrap();
+        assert_eq!(inventory.elves.len(), 5);
 
-    println!("The largest bag contains: {} calories", bags[0]);
+        let top = inventory.top(3);
+        assert_eq!(
+            top.iter().map(|elf| elf.index).collect::<Vec<_>>(),
+            vec![4, 3, 5]
+        );
+        assert_eq!(top.iter().map(|elf| elf.calories()).sum::<usize>(), 45000);
+        assert_eq!(inventory.top(1)[0].calories(), 24000);
+        assert_eq!(inventory.top(10).len(), 5);
+        assert!(inventory.top(0).is_empty());
+    }
+
+    #[test]
+    fn messy_input() {
+        // No trailing blank line, CRLF line endings, and a tie for the top spot
+        let inventory = Inventory::try_from("100\r\n200\r\n\r\n300\r\n\r\n\r\n50\r\n250").unwrap();
+        assert_eq!(inventory.elves.len(), 3);
+        assert_eq!(inventory.elves[2].items, vec![50, 250]);
+        assert_eq!(
+            inventory
+                .top(2)
+                .iter()
+                .map(|elf| elf.index)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
 
-    let largest3: usize = bags.iter().take(3).sum();
-    println!("The largest 3 bags have: {} calories", largest3);
+        assert_eq!(
+            Inventory::try_from("100\n\n2x0").err(),
+            Some(String::from("Elf 2 has an invalid item \"2x0\""))
+        );
+    }
 }
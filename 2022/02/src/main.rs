@@ -1,107 +1,403 @@
-use std::fs;
+use std::{cmp::Reverse, env, fmt, fs};
 
-#[derive(PartialEq, Clone, Copy)]
-enum Shape {
-    Rock,
-    Paper,
-    Scissors,
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Outcome {
+    Lose,
+    Draw,
+    Win,
 }
 
-impl Shape {
-    fn from_enemy_action(action: char) -> Self {
-        match action {
-            'A' => Self::Rock,
-            'B' => Self::Paper,
-            'C' => Self::Scissors,
-            x => panic!("Unknown enemy action {}", x),
+const OUTCOMES: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+/// A hand game where the shapes sit in a circle, and each shape beats the half of
+/// the other shapes just before it, which only works with an odd number of shapes
+///
+/// With rock, paper, scissors, paper beats rock, scissors beat paper, and rock
+/// wraps around to beat scissors.
+struct Game {
+    shapes: Vec<&'static str>,
+    /// What playing each shape is worth
+    shape_scores: Vec<usize>,
+    /// What losing, drawing and winning are worth
+    outcome_scores: [usize; 3],
+    /// The letters in the strategy guide for each shape the opponent plays
+    opponent_letters: Vec<char>,
+    /// The letters in the strategy guide for each shape we could play
+    response_letters: Vec<char>,
+    /// The letters in the strategy guide for losing, drawing and winning
+    outcome_letters: [char; 3],
+}
+
+impl Game {
+    fn new(
+        shapes: Vec<&'static str>,
+        shape_scores: Vec<usize>,
+        outcome_scores: [usize; 3],
+        opponent_letters: Vec<char>,
+        response_letters: Vec<char>,
+        outcome_letters: [char; 3],
+    ) -> Result<Self, &'static str> {
+        if shapes.len().is_multiple_of(2) {
+            return Err("A fair game needs an odd number of shapes");
+        }
+        // With a single shape every round is a draw, so there's no way to win or lose
+        if shapes.len() < 3 {
+            return Err("A game needs at least three shapes");
+        }
+        if [
+            shape_scores.len(),
+            opponent_letters.len(),
+            response_letters.len(),
+        ]
+        .iter()
+        .any(|&len| len != shapes.len())
+        {
+            return Err("Every shape needs a score and a letter for each player");
         }
+
+        Ok(Self {
+            shapes,
+            shape_scores,
+            outcome_scores,
+            opponent_letters,
+            response_letters,
+            outcome_letters,
+        })
     }
 
-    fn from_response(action: char) -> Self {
-        match action {
-            'X' => Self::Rock,
-            'Y' => Self::Paper,
-            'Z' => Self::Scissors,
-            x => panic!("Unknown response {}", x),
-        }
+    fn rock_paper_scissors() -> Self {
+        Self::new(
+            vec!["Rock", "Paper", "Scissors"],
+            vec![1, 2, 3],
+            [0, 3, 6],
+            vec!['A', 'B', 'C'],
+            vec!['X', 'Y', 'Z'],
+            ['X', 'Y', 'Z'],
+        )
+        .unwrap()
     }
 
-    fn score(&self) -> usize {
-        match &self {
-            Self::Rock => 1,
-            Self::Paper => 2,
-            Self::Scissors => 3,
-        }
+    fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::new(
+            vec!["Rock", "Spock", "Paper", "Lizard", "Scissors"],
+            vec![1, 2, 3, 4, 5],
+            [0, 3, 6],
+            vec!['A', 'B', 'C', 'D', 'E'],
+            vec!['V', 'W', 'X', 'Y', 'Z'],
+            ['X', 'Y', 'Z'],
+        )
+        .unwrap()
     }
 
-    fn match_score_against(&self, shape: &Self) -> usize {
-        if self == shape {
-            return 3;
+    /// How a round goes for us when we play `ours` and the opponent plays `theirs`
+    fn outcome(&self, ours: usize, theirs: usize) -> Outcome {
+        let n = self.shapes.len();
+        match (ours + n - theirs) % n {
+            0 => Outcome::Draw,
+            distance if distance <= n / 2 => Outcome::Win,
+            _ => Outcome::Lose,
         }
-        if match &self {
-            Self::Rock => shape == &Self::Scissors,
-            Self::Paper => shape == &Self::Rock,
-            Self::Scissors => shape == &Self::Paper,
-        } {
-            6
-        } else {
-            0
+    }
+
+    /// The shape to play to get an outcome, picking the best scoring one if there's a choice
+    fn respond(&self, theirs: usize, outcome: Outcome) -> usize {
+        (0..self.shapes.len())
+            .filter(|&ours| self.outcome(ours, theirs) == outcome)
+            .max_by_key(|&ours| (self.shape_scores[ours], usize::MAX - ours))
+            .unwrap()
+    }
+
+    fn score(&self, ours: usize, theirs: usize) -> usize {
+        self.shape_scores[ours] + self.outcome_scores[self.outcome(ours, theirs) as usize]
+    }
+
+    fn opponent_shape(&self, letter: char) -> Result<usize, String> {
+        self.opponent_letters
+            .iter()
+            .position(|&l| l == letter)
+            .ok_or_else(|| format!("Unknown opponent shape {letter}"))
+    }
+
+    /// Score a strategy guide, reading the second column as the shape to play
+    fn score_as_shapes(&self, guide: &[(char, char)]) -> Result<usize, String> {
+        guide.iter().try_fold(0, |total, &(opponent, response)| {
+            let ours = self
+                .response_letters
+                .iter()
+                .position(|&l| l == response)
+                .ok_or_else(|| format!("Unknown response {response}"))?;
+            Ok(total + self.score(ours, self.opponent_shape(opponent)?))
+        })
+    }
+
+    /// Score a strategy guide, reading the second column as how the round should end
+    fn score_as_outcomes(&self, guide: &[(char, char)]) -> Result<usize, String> {
+        guide.iter().try_fold(0, |total, &(opponent, response)| {
+            let outcome = self
+                .outcome_letters
+                .iter()
+                .position(|&l| l == response)
+                .map(|i| OUTCOMES[i])
+                .ok_or_else(|| format!("Unknown outcome {response}"))?;
+            let theirs = self.opponent_shape(opponent)?;
+            Ok(total + self.score(self.respond(theirs, outcome), theirs))
+        })
+    }
+}
+
+/// One way of reading the second column of a strategy guide
+#[derive(PartialEq, Debug)]
+enum Reading {
+    /// The shape to play for each response letter
+    Shapes(Vec<usize>),
+    /// The outcome to aim for for each response letter
+    Outcomes(Vec<Outcome>),
+}
+
+/// A reading of the strategy guide, and the score we'd get following it
+struct Interpretation<'a> {
+    game: &'a Game,
+    reading: Reading,
+    score: usize,
+}
+
+impl fmt::Display for Interpretation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>6}:", self.score)?;
+        for (i, letter) in self.game.response_letters.iter().enumerate() {
+            match &self.reading {
+                Reading::Shapes(shapes) => write!(f, " {letter}={}", self.game.shapes[shapes[i]])?,
+                Reading::Outcomes(outcomes) => write!(f, " {letter}={:?}", outcomes[i])?,
+            }
         }
+        Ok(())
+    }
+}
+
+/// Every possible way each response letter could map to a shape or to an outcome,
+/// including several letters meaning the same thing, from highest to lowest score
+fn analyse<'a>(game: &'a Game, guide: &[(char, char)]) -> Result<Vec<Interpretation<'a>>, String> {
+    let letters = game.response_letters.len();
+
+    // Count each kind of round once, so each reading is quick to score
+    let mut rounds = vec![vec![0; letters]; game.shapes.len()];
+    for &(opponent, response) in guide {
+        let response = game
+            .response_letters
+            .iter()
+            .position(|&l| l == response)
+            .ok_or_else(|| format!("Unknown response {response}"))?;
+        rounds[game.opponent_shape(opponent)?][response] += 1;
     }
+    let score = |meaning: &dyn Fn(usize, usize) -> usize| -> usize {
+        rounds
+            .iter()
+            .enumerate()
+            .flat_map(|(theirs, counts)| {
+                counts
+                    .iter()
+                    .enumerate()
+                    .map(move |(response, count)| count * meaning(theirs, response))
+            })
+            .sum()
+    };
 
-    fn score_against(&self, shape: &Self) -> usize {
-        self.match_score_against(shape) + self.score()
-    }
-
-    fn score_using_action(&self, action: char) -> usize {
-        let chosen_shape = match action {
-            'X' => match &self {
-                // lose
-                Self::Rock => Self::Scissors,
-                Self::Paper => Self::Rock,
-                Self::Scissors => Self::Paper,
-            },
-            'Y' => *self, // draw
-            'Z' => match &self {
-                // win
-                Self::Rock => Self::Paper,
-                Self::Paper => Self::Scissors,
-                Self::Scissors => Self::Rock,
-            },
-            x => panic!("Unknown action {}", x),
-        };
-        chosen_shape.score_against(self)
+    let mut interpretations = Vec::new();
+    for shapes in mappings(letters, game.shapes.len()) {
+        interpretations.push(Interpretation {
+            game,
+            score: score(&|theirs, response| game.score(shapes[response], theirs)),
+            reading: Reading::Shapes(shapes),
+        });
     }
+    for outcomes in mappings(letters, OUTCOMES.len()) {
+        let outcomes: Vec<Outcome> = outcomes.into_iter().map(|i| OUTCOMES[i]).collect();
+        interpretations.push(Interpretation {
+            game,
+            score: score(&|theirs, response| {
+                game.score(game.respond(theirs, outcomes[response]), theirs)
+            }),
+            reading: Reading::Outcomes(outcomes),
+        });
+    }
+
+    interpretations.sort_by_key(|interpretation| Reverse(interpretation.score));
+    Ok(interpretations)
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").unwrap();
+/// Every way of picking one of `choices` for each of `length` letters
+fn mappings(length: usize, choices: usize) -> Vec<Vec<usize>> {
+    (0..length).fold(vec![Vec::new()], |mappings, _| {
+        mappings
+            .into_iter()
+            .flat_map(|mapping| {
+                (0..choices).map(move |choice| {
+                    let mut mapping = mapping.clone();
+                    mapping.push(choice);
+                    mapping
+                })
+            })
+            .collect()
+    })
+}
 
-    let input = input
+fn parse_guide(input: &str) -> Vec<(char, char)> {
+    input
         .lines()
         .take_while(|line| !line.is_empty())
         .map(|line| line.chars().filter(|char| !char.is_whitespace()))
-        .map(|mut chars| (chars.next().unwrap(), chars.next().unwrap()));
-
-    let total_score: usize = input
-        .clone()
-        .map(|actions| {
-            (
-                Shape::from_enemy_action(actions.0),
-                Shape::from_response(actions.1),
-            )
-        })
-        .map(|shapes| shapes.1.score_against(&shapes.0))
-        .sum();
+        .map(|mut chars| (chars.next().unwrap(), chars.next().unwrap()))
+        .collect()
+}
 
-    println!("The total score is: {}", total_score);
+fn main() {
+    let input = fs::read_to_string("input.txt").unwrap();
+    let guide = parse_guide(&input);
+    let game = if env::args().any(|arg| arg == "--lizard-spock") {
+        Game::rock_paper_scissors_lizard_spock()
+    } else {
+        Game::rock_paper_scissors()
+    };
 
-    let total_score_part_2: usize = input
-        .map(|actions| Shape::from_enemy_action(actions.0).score_using_action(actions.1))
-        .sum();
+    let total_score = game.score_as_shapes(&guide).unwrap();
+    println!("The total score is: {}", total_score);
 
+    let total_score_part_2 = game.score_as_outcomes(&guide).unwrap();
     println!(
         "The total score with corrected instructions is: {}",
         total_score_part_2
     );
+
+    // Try every way the guide could be read with `--analyse`
+    if env::args().any(|arg| arg == "--analyse") {
+        let interpretations = analyse(&game, &guide).unwrap();
+        println!("\nBest reading:  {}", interpretations[0]);
+        println!("Worst reading: {}", interpretations.last().unwrap());
+        println!("\nAll {} readings:", interpretations.len());
+        for interpretation in &interpretations {
+            println!("{interpretation}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn example() {
+        let guide = parse_guide("A Y\nB X\nC Z\n");
+        let game = Game::rock_paper_scissors();
+        assert_eq!(game.score_as_shapes(&guide), Ok(15));
+        assert_eq!(game.score_as_outcomes(&guide), Ok(12));
+        assert_eq!(
+            game.score_as_shapes(&parse_guide("A W")),
+            Err(String::from("Unknown response W"))
+        );
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name| game.shapes.iter().position(|&s| s == name).unwrap();
+
+        for (winner, loser) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert_eq!(game.outcome(shape(winner), shape(loser)), Outcome::Win);
+            assert_eq!(game.outcome(shape(loser), shape(winner)), Outcome::Lose);
+        }
+
+        // Both Rock and Spock beat Scissors, but Spock scores more
+        assert_eq!(
+            game.respond(shape("Scissors"), Outcome::Win),
+            shape("Spock")
+        );
+        // Spock (2) + win (6), then Lizard (4) + draw (3)
+        assert_eq!(game.score_as_outcomes(&parse_guide("E Z\nD Y")), Ok(15));
+    }
+
+    #[test]
+    fn analysis() {
+        let game = Game::rock_paper_scissors();
+        let guide = parse_guide("A Y\nB X\nC Z\n");
+        let interpretations = analyse(&game, &guide).unwrap();
+        assert_eq!(interpretations.len(), 27 + 27);
+
+        // Both readings from the puzzle are in there
+        let score = |reading: Reading| {
+            interpretations
+                .iter()
+                .find(|interpretation| interpretation.reading == reading)
+                .unwrap()
+                .score
+        };
+        assert_eq!(score(Reading::Shapes(vec![0, 1, 2])), 15);
+        assert_eq!(
+            score(Reading::Outcomes(vec![
+                Outcome::Lose,
+                Outcome::Draw,
+                Outcome::Win
+            ])),
+            12
+        );
+
+        // Winning every round, either by picking the right shapes or aiming to win
+        let best = &interpretations[0];
+        assert_eq!(best.score, 8 + 9 + 7);
+        assert_eq!(best.to_string(), "    24: X=Scissors Y=Paper Z=Rock");
+        assert_eq!(
+            score(Reading::Outcomes(vec![
+                Outcome::Win,
+                Outcome::Win,
+                Outcome::Win
+            ])),
+            24
+        );
+        // Losing every round
+        assert_eq!(interpretations.last().unwrap().score, 3 + 1 + 2);
+    }
+
+    #[test]
+    fn invalid_games() {
+        assert!(Game::new(
+            vec!["A", "B"],
+            vec![1, 2],
+            [0, 3, 6],
+            vec!['A', 'B'],
+            vec!['X', 'Y'],
+            ['X', 'Y', 'Z']
+        )
+        .is_err());
+        assert!(Game::new(
+            vec!["A"],
+            vec![1, 2],
+            [0, 3, 6],
+            vec!['A'],
+            vec!['X'],
+            ['X', 'Y', 'Z']
+        )
+        .is_err());
+        assert_eq!(
+            Game::new(
+                vec!["A"],
+                vec![1],
+                [0, 3, 6],
+                vec!['A'],
+                vec!['X'],
+                ['X', 'Y', 'Z']
+            )
+            .err(),
+            Some("A game needs at least three shapes")
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, fs};
+use std::{collections::HashSet, env, fmt, fs, process};
 
 const PRIORITIES: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
@@ -16,24 +16,35 @@ struct Rucksack {
 }
 
 impl Rucksack {
-    fn fill(items: &str) -> Self {
-        if items.len() % 2 != 0 {
-            panic!("Tried to fill a bag with an uneven number of items");
+    fn fill(items: &str) -> Result<Self, usize> {
+        let items: Vec<char> = items.chars().collect();
+        if !items.len().is_multiple_of(2) {
+            return Err(items.len());
         }
-        let count = items.len() / 2;
-        let items = items.chars();
-        Rucksack {
-            compartment1: items.clone().take(count).collect(),
-            compartment2: items.skip(count).take(count).collect(),
+        let (compartment1, compartment2) = items.split_at(items.len() / 2);
+        Ok(Rucksack {
+            compartment1: compartment1.to_vec(),
+            compartment2: compartment2.to_vec(),
+        })
+    }
+
+    /// Every different item in both compartments, in the order they appear in the first
+    fn common_items(&self) -> Vec<char> {
+        let mut common = Vec::new();
+        for item in &self.compartment1 {
+            if self.compartment2.contains(item) && !common.contains(item) {
+                common.push(*item);
+            }
         }
+        common
     }
 
-    fn common_item(&self) -> char {
-        *self
-            .compartment1
-            .iter()
-            .find(|item| self.compartment2.iter().any(|i| i == *item))
-            .expect("No common item found")
+    /// The item packed in both compartments, if there's exactly one
+    fn common_item(&self) -> Option<char> {
+        match self.common_items()[..] {
+            [item] => Some(item),
+            _ => None,
+        }
     }
 
     fn contents(&self) -> Vec<char> {
@@ -42,49 +53,291 @@ impl Rucksack {
 }
 
 struct ElfGroup {
-    badge: Option<char>,
+    /// Items carried by every elf in the group, sorted
+    candidates: Vec<char>,
 }
 
 impl ElfGroup {
-    fn new(sacks: &[Rucksack]) -> Self {
-        // Find common item in all three sacks
+    fn new<'a>(sacks: impl IntoIterator<Item = &'a Rucksack>) -> Self {
+        // Find common items in all the sacks
         let set: HashSet<_> = sacks
-            .iter()
-            .map(|sack| HashSet::from_iter(sack.contents().into_iter()))
+            .into_iter()
+            .map(|sack| HashSet::from_iter(sack.contents()))
             .reduce(|intersection, set| {
                 intersection
                     .into_iter()
                     .filter(|char| set.contains(char))
                     .collect::<HashSet<_>>()
             })
-            .unwrap();
-        ElfGroup {
-            badge: set.into_iter().next(),
+            .unwrap_or_default();
+        let mut candidates: Vec<char> = set.into_iter().collect();
+        candidates.sort_unstable();
+        ElfGroup { candidates }
+    }
+
+    /// The item carried by every elf, if there's exactly one
+    fn badge(&self) -> Option<char> {
+        match self.candidates[..] {
+            [badge] => Some(badge),
+            _ => None,
+        }
+    }
+}
+
+/// Something wrong with the list of rucksacks, with line numbers starting from 1
+#[derive(PartialEq, Debug)]
+enum Problem {
+    UnevenRucksack { line: usize, items: usize },
+    CommonItems { line: usize, items: Vec<char> },
+    UnknownItem { line: usize, item: char },
+    IncompleteGroup { group: usize, elves: usize },
+    UnevenGroup { group: usize },
+    Badges { group: usize, candidates: Vec<char> },
+    UnknownBadge { group: usize, badge: char },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnevenRucksack { line, items } => {
+                write!(f, "Line {line}: {items} items can't be split evenly")
+            }
+            Self::CommonItems { line, items } if items.is_empty() => {
+                write!(f, "Line {line}: no item is in both compartments")
+            }
+            Self::CommonItems { line, items } => {
+                write!(f, "Line {line}: {items:?} are all in both compartments")
+            }
+            Self::UnknownItem { line, item } => {
+                write!(
+                    f,
+                    "Line {line}: {item:?} in both compartments has no priority"
+                )
+            }
+            Self::IncompleteGroup { group, elves } => {
+                write!(f, "Group {group}: only has {elves} elves")
+            }
+            Self::UnevenGroup { group } => {
+                write!(
+                    f,
+                    "Group {group}: the badge can't be checked with uneven rucksacks"
+                )
+            }
+            Self::Badges { group, candidates } if candidates.is_empty() => {
+                write!(f, "Group {group}: no item is carried by every elf")
+            }
+            Self::Badges { group, candidates } => {
+                write!(
+                    f,
+                    "Group {group}: {candidates:?} are all carried by every elf"
+                )
+            }
+            Self::UnknownBadge { group, badge } => {
+                write!(f, "Group {group}: the badge {badge:?} has no priority")
+            }
+        }
+    }
+}
+
+/// Find every rucksack without exactly one common item, and every group of
+/// `group_size` elves without exactly one badge, or with one that has no priority
+fn validate(lines: &[&str], group_size: usize) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut sacks = Vec::new();
+
+    for (i, items) in lines.iter().enumerate() {
+        match Rucksack::fill(items) {
+            Ok(sack) => {
+                let common = sack.common_items();
+                match common[..] {
+                    [item] if !PRIORITIES.contains(item) => {
+                        problems.push(Problem::UnknownItem { line: i + 1, item })
+                    }
+                    [_] => {}
+                    _ => problems.push(Problem::CommonItems {
+                        line: i + 1,
+                        items: common,
+                    }),
+                }
+                sacks.push(Some(sack));
+            }
+            Err(items) => {
+                problems.push(Problem::UnevenRucksack { line: i + 1, items });
+                sacks.push(None);
+            }
+        }
+    }
+
+    for (i, group) in sacks.chunks(group_size).enumerate() {
+        if group.len() < group_size {
+            problems.push(Problem::IncompleteGroup {
+                group: i + 1,
+                elves: group.len(),
+            });
+            continue;
+        }
+        // Without every elf's sack, the badge would only be a guess
+        if group.iter().any(Option::is_none) {
+            problems.push(Problem::UnevenGroup { group: i + 1 });
+            continue;
+        }
+        let candidates = ElfGroup::new(group.iter().flatten()).candidates;
+        match candidates[..] {
+            [badge] if !PRIORITIES.contains(badge) => problems.push(Problem::UnknownBadge {
+                group: i + 1,
+                badge,
+            }),
+            [_] => {}
+            _ => problems.push(Problem::Badges {
+                group: i + 1,
+                candidates,
+            }),
         }
     }
+
+    problems
 }
 
 fn main() {
     let input = fs::read_to_string("input.txt").unwrap();
+    let lines: Vec<&str> = input.lines().take_while(|line| !line.is_empty()).collect();
+
+    let args: Vec<String> = env::args().collect();
+    let group_size = match args.iter().position(|arg| arg == "--group-size") {
+        Some(i) => match args.get(i + 1).and_then(|size| size.parse().ok()) {
+            Some(size) if size >= 1 => size,
+            _ => {
+                eprintln!("--group-size needs a number of elves, at least 1");
+                process::exit(1);
+            }
+        },
+        None => 3,
+    };
 
-    let priority_sum: usize = input
-        .lines()
-        .take_while(|line| !line.is_empty())
-        .map(Rucksack::fill)
-        .map(|sack| priority(sack.common_item()))
+    let problems = validate(&lines, group_size);
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{problem}");
+        }
+        process::exit(1);
+    }
+
+    let sacks: Vec<Rucksack> = lines
+        .iter()
+        .map(|items| Rucksack::fill(items).unwrap())
+        .collect();
+
+    let priority_sum: usize = sacks
+        .iter()
+        .map(|sack| priority(sack.common_item().unwrap()))
         .sum();
 
     println!("The total priority is {}", priority_sum);
 
-    let group_sum: usize = input
-        .lines()
-        .take_while(|line| !line.is_empty())
-        .map(Rucksack::fill)
-        .collect::<Vec<_>>()
-        .chunks(3)
+    let group_sum: usize = sacks
+        .chunks(group_size)
         .map(ElfGroup::new)
-        .map(|group| priority(group.badge.unwrap()))
+        .map(|group| priority(group.badge().unwrap()))
         .sum();
 
     println!("The total group priority is {}", group_sum);
 }
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    const EXAMPLE: [&str; 6] = [
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+        "PmmdzqPrVvPwwTWBwg",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+        "ttgJtRGJQctTZtZT",
+        "CrZsJsPPZsGzwwsLwLmpwMDw",
+    ];
+
+    #[test]
+    fn example() {
+        assert!(validate(&EXAMPLE, 3).is_empty());
+
+        let sacks: Vec<Rucksack> = EXAMPLE
+            .iter()
+            .map(|items| Rucksack::fill(items).unwrap())
+            .collect();
+        assert_eq!(sacks[0].common_item(), Some('p'));
+        assert_eq!(ElfGroup::new(&sacks[..3]).badge(), Some('r'));
+        assert_eq!(ElfGroup::new(&sacks[3..]).badge(), Some('Z'));
+    }
+
+    #[test]
+    fn problems() {
+        let lines = ["abcabd", "abcdef", "abcdefg", "abxabx", "xyzxyz", "axya"];
+        assert_eq!(
+            validate(&lines, 4),
+            vec![
+                Problem::CommonItems {
+                    line: 1,
+                    items: vec!['a', 'b']
+                },
+                Problem::CommonItems {
+                    line: 2,
+                    items: vec![]
+                },
+                Problem::UnevenRucksack { line: 3, items: 7 },
+                Problem::CommonItems {
+                    line: 4,
+                    items: vec!['a', 'b', 'x']
+                },
+                Problem::CommonItems {
+                    line: 5,
+                    items: vec!['x', 'y', 'z']
+                },
+                Problem::UnevenGroup { group: 1 },
+                Problem::IncompleteGroup { group: 2, elves: 2 },
+            ]
+        );
+        assert_eq!(
+            validate(&lines[..2], 2),
+            vec![
+                Problem::CommonItems {
+                    line: 1,
+                    items: vec!['a', 'b']
+                },
+                Problem::CommonItems {
+                    line: 2,
+                    items: vec![]
+                },
+                Problem::Badges {
+                    group: 1,
+                    candidates: vec!['a', 'b', 'c', 'd']
+                },
+            ]
+        );
+        assert_eq!(
+            Problem::Badges {
+                group: 1,
+                candidates: vec![]
+            }
+            .to_string(),
+            "Group 1: no item is carried by every elf"
+        );
+    }
+
+    #[test]
+    fn items_without_priorities() {
+        // Each sack only shares a digit, which would have made `priority` panic
+        let lines = ["1a1b", "1c1d", "1e1f"];
+        assert_eq!(
+            validate(&lines, 3),
+            vec![
+                Problem::UnknownItem { line: 1, item: '1' },
+                Problem::UnknownItem { line: 2, item: '1' },
+                Problem::UnknownItem { line: 3, item: '1' },
+                Problem::UnknownBadge {
+                    group: 1,
+                    badge: '1'
+                },
+            ]
+        );
+    }
+}
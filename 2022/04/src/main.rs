@@ -1,5 +1,6 @@
-use std::fs;
+use std::{env, fs};
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 struct Range {
     from: usize,
     to: usize,
@@ -24,6 +25,90 @@ impl Range {
     }
 }
 
+/// Which elf was given an assignment, as the line of the pair and the position in the pair
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Elf {
+    line: usize,
+    position: usize,
+}
+
+/// A run of sections covered by the same number of elves
+#[derive(PartialEq, Debug)]
+struct Segment {
+    from: usize,
+    to: usize,
+    elves: usize,
+}
+
+struct Coverage {
+    /// Every section from the lowest assigned to the highest, in order
+    segments: Vec<Segment>,
+    /// Elves whose whole assignment is also covered by a single other elf
+    redundant: Vec<Elf>,
+}
+
+impl Coverage {
+    /// Sweeps across the sections, so it takes `O(n log n)` time for `n` assignments
+    /// rather than comparing every pair of them
+    fn new(assignments: &[(Elf, Range)]) -> Self {
+        // Each assignment starts covering at `from` and stops after `to`
+        let mut events: Vec<(usize, isize)> = assignments
+            .iter()
+            .flat_map(|(_, range)| [(range.from, 1), (range.to + 1, -1)])
+            .collect();
+        events.sort_unstable();
+
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut elves = 0;
+        for (i, (section, change)) in events.iter().enumerate() {
+            elves += change;
+            match events.get(i + 1) {
+                Some((next, _)) if next > section => segments.push(Segment {
+                    from: *section,
+                    to: next - 1,
+                    elves: elves as usize,
+                }),
+                _ => {}
+            }
+        }
+
+        // Going through assignments by where they start, longest first, an assignment
+        // is contained by an earlier one if it doesn't go past the furthest they reach
+        let mut order: Vec<&(Elf, Range)> = assignments.iter().collect();
+        order.sort_by_key(|(_, range)| (range.from, usize::MAX - range.to));
+        let mut redundant = Vec::new();
+        let mut furthest = None;
+        for (elf, range) in order {
+            if furthest.is_some_and(|furthest| range.to <= furthest) {
+                redundant.push(*elf);
+            }
+            furthest = furthest.max(Some(range.to));
+        }
+        redundant.sort_by_key(|elf| (elf.line, elf.position));
+
+        Self {
+            segments,
+            redundant,
+        }
+    }
+
+    /// How many elves are covering a section
+    fn elves_covering(&self, section: usize) -> usize {
+        let i = self
+            .segments
+            .partition_point(|segment| segment.to < section);
+        self.segments
+            .get(i)
+            .filter(|segment| segment.from <= section)
+            .map_or(0, |segment| segment.elves)
+    }
+
+    /// Runs of sections between assignments that nobody is covering
+    fn uncovered(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter().filter(|segment| segment.elves == 0)
+    }
+}
+
 fn main() {
     let input = fs::read_to_string("input.txt").unwrap();
 
@@ -49,8 +134,122 @@ fn main() {
     );
 
     let overlapping = input
+        .clone()
         .filter(|(range_1, range_2)| range_1.overlaps(range_2))
         .count();
 
     println!("There are {} assignment pairs that overlap", overlapping);
+
+    // Look at how well every section is covered with `--coverage`
+    if env::args().any(|arg| arg == "--coverage") {
+        let assignments: Vec<(Elf, Range)> = input
+            .enumerate()
+            .flat_map(|(i, (range_1, range_2))| {
+                [(1, range_1), (2, range_2)].map(|(position, range)| {
+                    (
+                        Elf {
+                            line: i + 1,
+                            position,
+                        },
+                        range,
+                    )
+                })
+            })
+            .collect();
+        let coverage = Coverage::new(&assignments);
+
+        println!("\nElves covering each section:");
+        if let (Some(first), Some(last)) = (coverage.segments.first(), coverage.segments.last()) {
+            for section in first.from..=last.to {
+                println!("  {section}: {}", coverage.elves_covering(section));
+            }
+        }
+        println!("Sections nobody is covering:");
+        for segment in coverage.uncovered() {
+            println!("  {}-{}", segment.from, segment.to);
+        }
+        println!("Elves with redundant assignments:");
+        for elf in &coverage.redundant {
+            println!("  line {}, elf {}", elf.line, elf.position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn assignments(ranges: &[&str]) -> Vec<(Elf, Range)> {
+        ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let elf = Elf {
+                    line: i / 2 + 1,
+                    position: i % 2 + 1,
+                };
+                (elf, Range::new(range))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn coverage() {
+        let coverage = Coverage::new(&assignments(&[
+            "2-4", "6-8", "2-3", "4-5", "5-7", "7-9", "2-8", "3-7", "6-6", "4-6", "2-6", "4-8",
+        ]));
+
+        assert_eq!(coverage.elves_covering(1), 0);
+        assert_eq!(coverage.elves_covering(2), 4);
+        assert_eq!(coverage.elves_covering(6), 8);
+        assert_eq!(coverage.elves_covering(9), 1);
+        assert_eq!(coverage.uncovered().count(), 0);
+        assert_eq!(
+            coverage.segments.first(),
+            Some(&Segment {
+                from: 2,
+                to: 2,
+                elves: 4
+            })
+        );
+
+        // Only 2-8 (line 4, elf 1) and 7-9 (line 3, elf 2) aren't inside another assignment
+        assert_eq!(coverage.redundant.len(), 10);
+        assert!(!coverage.redundant.contains(&Elf {
+            line: 4,
+            position: 1
+        }));
+        assert!(!coverage.redundant.contains(&Elf {
+            line: 3,
+            position: 2
+        }));
+    }
+
+    #[test]
+    fn gaps_and_duplicates() {
+        let coverage = Coverage::new(&assignments(&["1-3", "8-9", "8-9", "5-5"]));
+        assert_eq!(
+            coverage.uncovered().collect::<Vec<_>>(),
+            vec![
+                &Segment {
+                    from: 4,
+                    to: 4,
+                    elves: 0
+                },
+                &Segment {
+                    from: 6,
+                    to: 7,
+                    elves: 0
+                }
+            ]
+        );
+        // Only one of two identical assignments is redundant
+        assert_eq!(
+            coverage.redundant,
+            vec![Elf {
+                line: 2,
+                position: 1
+            }]
+        );
+    }
 }
@@ -0,0 +1,106 @@
+use std::ops::AddAssign;
+
+/// How much work a crane did
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct LiftStats {
+    /// Times the crane picked crates up and put them down
+    pub lifts: usize,
+    pub crates_moved: usize,
+}
+
+impl AddAssign for LiftStats {
+    fn add_assign(&mut self, other: Self) {
+        self.lifts += other.lifts;
+        self.crates_moved += other.crates_moved;
+    }
+}
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    /// The most crates the crane can pick up at once
+    fn capacity(&self) -> usize;
+
+    /// Move `amount` crates from the top of one stack to another, as many as the
+    /// crane can hold at a time, keeping the order of the crates it picks up
+    fn move_crates(
+        &self,
+        stacks: &mut [Vec<char>],
+        amount: usize,
+        from: usize,
+        to: usize,
+    ) -> LiftStats {
+        let mut stats = LiftStats::default();
+        let mut remaining = amount;
+        while remaining > 0 {
+            let grip = remaining.min(self.capacity());
+            let height = stacks[from].len();
+            let crates = stacks[from].split_off(height - grip);
+            stacks[to].extend(crates);
+
+            remaining -= grip;
+            stats.lifts += 1;
+            stats.crates_moved += grip;
+        }
+        stats
+    }
+}
+
+/// Moves one crate at a time, so a stack of crates ends up reversed
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9000")
+    }
+
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+/// Moves any number of crates at once
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        String::from("CrateMover 9001")
+    }
+
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// Can only grip a few crates at once, so moves bigger stacks in chunks
+pub struct CappedCrane(pub usize);
+
+impl Crane for CappedCrane {
+    fn name(&self) -> String {
+        format!("Crane holding {} crates", self.0)
+    }
+
+    fn capacity(&self) -> usize {
+        self.0.max(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chunks() {
+        let stacks = vec![vec!['A', 'B', 'C', 'D', 'E'], vec![]];
+        let run = |crane: &dyn Crane| {
+            let mut stacks = stacks.clone();
+            let stats = crane.move_crates(&mut stacks, 5, 0, 1);
+            (stacks[1].iter().collect::<String>(), stats.lifts)
+        };
+
+        assert_eq!(run(&CrateMover9000), (String::from("EDCBA"), 5));
+        assert_eq!(run(&CrateMover9001), (String::from("ABCDE"), 1));
+        assert_eq!(run(&CappedCrane(2)), (String::from("DEBCA"), 3));
+        assert_eq!(run(&CappedCrane(5)), (String::from("ABCDE"), 1));
+    }
+}
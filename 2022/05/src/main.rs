@@ -1,59 +1,256 @@
-use std::fs;
+use std::{env, fmt, fs, process};
+
+use aoc_common::parse::record;
+use crane::{CappedCrane, Crane, CrateMover9000, CrateMover9001, LiftStats};
+use planner::{plan, puzzle_text, Goal};
+use render::render;
+
+mod crane;
+mod planner;
+mod render;
+
+/// How many layouts the planner looks at before giving up
+const PLAN_LIMIT: usize = 200_000;
 
 struct Action {
     amount: usize,
     from: usize,
     to: usize,
+    /// Where the action is in the input, starting from 1
+    line: usize,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+impl Action {
+    /// Make sure the action can be done with the stacks as they are
+    fn check(&self, stacks: &[Vec<char>]) -> Result<(), MoveError> {
+        for stack in [self.from, self.to] {
+            if stack == 0 || stack > stacks.len() {
+                return Err(MoveError::NoSuchStack {
+                    line: self.line,
+                    stack,
+                    stacks: stacks.len(),
+                });
+            }
+        }
+        let height = stacks[self.from - 1].len();
+        if height < self.amount {
+            return Err(MoveError::NotEnoughCrates {
+                line: self.line,
+                stack: self.from,
+                height,
+                amount: self.amount,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum MoveError {
+    /// A line that isn't a `move N from A to B` action
+    InvalidAction { line: usize, text: String },
+    NoSuchStack {
+        line: usize,
+        stack: usize,
+        stacks: usize,
+    },
+    NotEnoughCrates {
+        line: usize,
+        stack: usize,
+        height: usize,
+        amount: usize,
+    },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAction { line, text } => {
+                write!(f, "Line {line}: {text:?} isn't a move")
+            }
+            Self::NoSuchStack {
+                line,
+                stack,
+                stacks,
+            } => write!(
+                f,
+                "Line {line}: there is no stack {stack}, only stacks 1 to {stacks}"
+            ),
+            Self::NotEnoughCrates {
+                line,
+                stack,
+                height,
+                amount,
+            } => write!(
+                f,
+                "Line {line}: can't move {amount} crates from stack {stack}, which has {height}"
+            ),
+        }
+    }
 }
 
-enum CrateMoverModel {
-    CrateMover9000,
-    CrateMover9001,
+/// Report a problem with the input or the arguments and stop
+fn exit(error: impl fmt::Display) -> ! {
+    eprintln!("{error}");
+    process::exit(1);
+}
+
+/// The argument after `flag`, if it was given, stopping if there's nothing after it
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let i = args.iter().position(|arg| arg == flag)?;
+    match args.get(i + 1) {
+        Some(value) => Some(value),
+        None => exit(format!("{flag} needs a value")),
+    }
 }
 
 fn main() {
     let input = fs::read_to_string("input.txt").unwrap();
+    let (stacks, actions) = parse_input(&input).unwrap_or_else(|error| exit(error));
 
-    // Parse input
-    let stacks = parse_stacks(input.lines().take_while(|line| !line.is_empty()).collect());
-    let actions = parse_actions(
-        input
-            .lines()
-            .skip_while(|line| !line.is_empty())
-            .skip(1)
-            .collect(),
-    );
+    let run = |crane| run_actions(&stacks, &actions, crane).unwrap_or_else(|error| exit(error));
 
-    let top_of_stacks = run_actions(&stacks, &actions, CrateMoverModel::CrateMover9000);
+    let top_of_stacks = run(&CrateMover9000);
     println!("The top of all stacks is: {top_of_stacks}");
 
-    let top_of_stacks_9001 = run_actions(&stacks, &actions, CrateMoverModel::CrateMover9001);
+    let top_of_stacks_9001 = run(&CrateMover9001);
     println!("The top of all stacks using model 9001 is: {top_of_stacks_9001}");
+
+    // Compare cranes with `--capacity <N>` to add one that holds N crates at a time,
+    // watch a crane with `--replay [N]` to draw the stacks after every N actions,
+    // and `--final` to draw every crate left at the end.
+    // The crane is the CrateMover 9000 unless `--crane <9000|9001|capped>` picks another.
+    // Plan actions for it with `--plan <file>` to reach the stacks drawn
+    // in a file, or `--plan-tops <tops>` to reach the top crates (`.` for any)
+    let args: Vec<String> = env::args().collect();
+    let mut cranes: Vec<Box<dyn Crane>> = vec![Box::new(CrateMover9000), Box::new(CrateMover9001)];
+    let capacity: Option<usize> =
+        flag_value(&args, "--capacity").map(|capacity| match capacity.parse() {
+            Ok(capacity) if capacity >= 1 => capacity,
+            _ => exit(format!(
+                "Invalid capacity {capacity}, it needs to be at least 1"
+            )),
+        });
+    if let Some(capacity) = capacity {
+        cranes.push(Box::new(CappedCrane(capacity)));
+    }
+
+    let crane: Box<dyn Crane> = match flag_value(&args, "--crane") {
+        None | Some("9000") => Box::new(CrateMover9000),
+        Some("9001") => Box::new(CrateMover9001),
+        Some("capped") => match capacity {
+            Some(capacity) => Box::new(CappedCrane(capacity)),
+            None => exit("A capped crane needs a --capacity"),
+        },
+        Some(other) => exit(format!("Unknown crane {other}, pick 9000, 9001 or capped")),
+    };
+
+    println!();
+    for crane in &cranes {
+        let stats = replay(&mut stacks.clone(), &actions, crane.as_ref(), |_, _, _| {}).unwrap();
+        println!(
+            "{}: {} lifts to move {} crates",
+            crane.name(),
+            stats.lifts,
+            stats.crates_moved
+        );
+    }
+
+    let replay_every = args.iter().position(|arg| arg == "--replay").map(|i| {
+        args.get(i + 1)
+            .and_then(|every| every.parse().ok())
+            .unwrap_or(1)
+    });
+    let show_final = args.iter().any(|arg| arg == "--final");
+
+    if replay_every.is_some() || show_final {
+        let mut stacks = stacks.clone();
+        replay(
+            &mut stacks,
+            &actions,
+            crane.as_ref(),
+            |step, action, stacks| {
+                if replay_every.is_some_and(|every| (step + 1) % every == 0) {
+                    println!("\n{}: {action}", step + 1);
+                    print!("{}", render(stacks, Some((action.to - 1, action.amount))));
+                }
+            },
+        )
+        .unwrap();
+        if show_final {
+            println!("\nFinal stacks:");
+            print!("{}", render(&stacks, None));
+        }
+    }
+
+    let goal = if let Some(path) = flag_value(&args, "--plan") {
+        let target = fs::read_to_string(path)
+            .unwrap_or_else(|error| exit(format!("Couldn't read {path}: {error}")));
+        Some(Goal::Layout(parse_stacks(
+            target.lines().take_while(|line| !line.is_empty()).collect(),
+        )))
+    } else {
+        flag_value(&args, "--plan-tops").map(Goal::tops)
+    };
+    if let Some(goal) = goal {
+        match plan(&stacks, &goal, crane.as_ref(), PLAN_LIMIT) {
+            Ok(actions) => print!("\n{}", puzzle_text(&stacks, &actions)),
+            Err(error) => exit(error),
+        }
+    }
 }
 
-fn run_actions(stacks: &[Vec<char>], actions: &Vec<Action>, model: CrateMoverModel) -> String {
+fn run_actions(
+    stacks: &[Vec<char>],
+    actions: &[Action],
+    crane: &dyn Crane,
+) -> Result<String, MoveError> {
     // Clone stacks
     let mut stacks = stacks.to_owned();
+    replay(&mut stacks, actions, crane, |_, _, _| {})?;
 
-    // Run actions
-    for action in actions {
-        // Grab the crates to move
-        let mut gripper: Vec<char> = (0..action.amount)
-            .map(|_| stacks[action.from - 1].pop().unwrap())
-            .collect();
-
-        if matches!(model, CrateMoverModel::CrateMover9001) {
-            gripper.reverse();
-        }
+    // Find out what's on top of each stack, skipping any that end up empty
+    Ok(stacks.iter().filter_map(|stack| stack.last()).collect())
+}
 
-        // Deposit in their new home
-        gripper
-            .into_iter()
-            .for_each(|item| stacks[action.to - 1].push(item));
+/// Run every action on the stacks, calling `after_action` with how many actions
+/// came before it, the action, and the stacks afterwards
+///
+/// Stops at the first action that can't be done, leaving the stacks as they were before it
+fn replay(
+    stacks: &mut [Vec<char>],
+    actions: &[Action],
+    crane: &dyn Crane,
+    mut after_action: impl FnMut(usize, &Action, &[Vec<char>]),
+) -> Result<LiftStats, MoveError> {
+    let mut stats = LiftStats::default();
+    for (step, action) in actions.iter().enumerate() {
+        action.check(stacks)?;
+        stats += crane.move_crates(stacks, action.amount, action.from - 1, action.to - 1);
+        after_action(step, action, stacks);
     }
+    Ok(stats)
+}
 
-    // Find out what's on top of each stack
-    stacks.iter().map(|stack| stack.last().unwrap()).collect()
+/// Split the input into the starting stacks and the actions after them
+fn parse_input(input: &str) -> Result<(Vec<Vec<char>>, Vec<Action>), MoveError> {
+    let stacks: Vec<&str> = input.lines().take_while(|line| !line.is_empty()).collect();
+    let first_action_line = stacks.len() + 2;
+    let actions = parse_actions(
+        input
+            .lines()
+            .skip_while(|line| !line.is_empty())
+            .skip(1)
+            .collect(),
+        first_action_line,
+    )?;
+    Ok((parse_stacks(stacks), actions))
 }
 
 fn parse_stacks(stacks: Vec<&str>) -> Vec<Vec<char>> {
@@ -65,16 +262,17 @@ fn parse_stacks(stacks: Vec<&str>) -> Vec<Vec<char>> {
         .map(|line_chars| {
             line_chars
                 .chunks(4)
-                .map(|col| col.get(1).unwrap().to_owned())
+                .map(|col| col.get(1).copied().unwrap_or(' '))
                 .collect::<Vec<char>>()
         })
         .collect();
 
     (0..number_of_stacks)
         .map(|i| {
+            // Rows can be cut short when the stacks on the right are lower
             chars
                 .iter()
-                .map(|row| row[i])
+                .filter_map(|row| row.get(i).copied())
                 .filter(|x| !x.is_whitespace())
                 .rev()
                 .collect()
@@ -82,20 +280,182 @@ fn parse_stacks(stacks: Vec<&str>) -> Vec<Vec<char>> {
         .collect()
 }
 
-fn parse_actions(actions: Vec<&str>) -> Vec<Action> {
+/// Parse every action, where the first is on line `first_line` of the input
+///
+/// Blank lines at the end are ignored, but anywhere else they're invalid.
+fn parse_actions(mut actions: Vec<&str>, first_line: usize) -> Result<Vec<Action>, MoveError> {
+    while actions
+        .last()
+        .is_some_and(|action| action.trim().is_empty())
+    {
+        actions.pop();
+    }
+
     actions
         .iter()
-        .map(|action| {
-            let values: Vec<usize> = action
-                .split_whitespace()
-                .filter(|x| x != &"move" && x != &"from" && x != &"to")
-                .map(|x| x.parse().unwrap())
-                .collect();
-            Action {
-                amount: values[0],
-                from: values[1],
-                to: values[2],
+        .enumerate()
+        .map(|(i, action)| {
+            let line = first_line + i;
+            let words: Vec<&str> = action.split_whitespace().collect();
+            match (words.as_slice(), record(action)) {
+                (["move", _, "from", _, "to", _], Some([amount, from, to])) => Ok(Action {
+                    amount,
+                    from,
+                    to,
+                    line,
+                }),
+                _ => Err(MoveError::InvalidAction {
+                    line,
+                    text: action.to_string(),
+                }),
             }
         })
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn example() {
+        let (stacks, actions) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            run_actions(&stacks, &actions, &CrateMover9000),
+            Ok(String::from("CMZ"))
+        );
+        assert_eq!(
+            run_actions(&stacks, &actions, &CrateMover9001),
+            Ok(String::from("MCD"))
+        );
+        assert_eq!(
+            run_actions(&stacks, &actions, &CappedCrane(1)),
+            Ok(String::from("CMZ"))
+        );
+    }
+
+    #[test]
+    fn lift_stats() {
+        let (stacks, actions) = parse_input(EXAMPLE).unwrap();
+        let stats =
+            |crane: &dyn Crane| replay(&mut stacks.clone(), &actions, crane, |_, _, _| {}).unwrap();
+
+        let crates_moved = 1 + 3 + 2 + 1;
+        assert_eq!(
+            stats(&CrateMover9000),
+            LiftStats {
+                lifts: crates_moved,
+                crates_moved
+            }
+        );
+        assert_eq!(stats(&CrateMover9001).lifts, 4);
+        assert_eq!(stats(&CappedCrane(2)).lifts, 1 + 2 + 1 + 1);
+    }
+
+    #[test]
+    fn replay_frames() {
+        let (mut stacks, actions) = parse_input(EXAMPLE).unwrap();
+        let mut frames = Vec::new();
+        replay(
+            &mut stacks,
+            &actions,
+            &CrateMover9000,
+            |step, action, stacks| {
+                frames.push(format!(
+                    "{}: {action}\n{}",
+                    step + 1,
+                    render(stacks, Some((action.to - 1, action.amount)))
+                ))
+            },
+        )
+        .unwrap();
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(
+            frames[1],
+            "2: move 3 from 1 to 3\n        <Z>\n        <N>\n    [C] <D>\n    [M] [P]\n 1   2   3\n"
+        );
+        assert_eq!(
+            render(&stacks, None),
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3\n"
+        );
+    }
+
+    #[test]
+    fn invalid_moves() {
+        // The trailing spaces of the first row are missing
+        let input =
+            "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\nmove 2 from 3 to 1\nmove 1 from 1 to 4\n";
+        let (stacks, actions) = parse_input(input).unwrap();
+        assert_eq!(stacks[2], vec!['P']);
+
+        let error = run_actions(&stacks, &actions, &CrateMover9000).unwrap_err();
+        assert_eq!(
+            error,
+            MoveError::NotEnoughCrates {
+                line: 6,
+                stack: 3,
+                height: 1,
+                amount: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Line 6: can't move 2 crates from stack 3, which has 1"
+        );
+
+        let error = run_actions(&stacks, &actions[1..], &CrateMover9000).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 7: there is no stack 4, only stacks 1 to 3"
+        );
+    }
+
+    #[test]
+    fn invalid_actions() {
+        // Trailing blank lines are fine
+        let (_, actions) = parse_input(&format!("{EXAMPLE}\n\n")).unwrap();
+        assert_eq!(actions.len(), 4);
+
+        let input = EXAMPLE.replace("\n\nmove", "\n\n\nmove");
+        assert_eq!(
+            parse_input(&input).err(),
+            Some(MoveError::InvalidAction {
+                line: 6,
+                text: String::new()
+            })
+        );
+
+        let input = EXAMPLE.replace("move 3 from 1 to 3", "move 3 from 1");
+        assert_eq!(
+            parse_input(&input).err().unwrap().to_string(),
+            "Line 7: \"move 3 from 1\" isn't a move"
+        );
+
+        // Numbers that don't fit are invalid too, rather than panicking
+        for action in [
+            "move -1 from 2 to 1",
+            "move 99999999999999999999999 from 1 to 2",
+        ] {
+            let input = EXAMPLE.replace("move 2 from 2 to 1", action);
+            assert_eq!(
+                parse_input(&input).err(),
+                Some(MoveError::InvalidAction {
+                    line: 8,
+                    text: String::from(action)
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use std::rc::Rc;
+
+use aoc_common::hash::FastSet;
+
+use crate::{crane::Crane, render::render, Action};
+
+/// A move as an amount and the stacks it goes from and to, starting from 1
+type Move = [usize; 3];
+
+/// A packed layout, and the layout before it with the move between them
+type Step = (Rc<str>, Option<(usize, Move)>);
+
+/// Marks the end of each stack in a packed layout
+const STACK_END: char = '\0';
+
+/// The stacks a plan should end up with
+pub enum Goal {
+    /// Every crate in every stack has to match
+    Layout(Vec<Vec<char>>),
+    /// Just the top crate of each stack, with `None` for stacks that can have anything
+    Tops(Vec<Option<char>>),
+}
+
+impl Goal {
+    /// Parse tops like `CMZ`, using `.` for a stack that can have anything on top
+    pub fn tops(tops: &str) -> Self {
+        Goal::Tops(
+            tops.chars()
+                .map(|top| if top == '.' { None } else { Some(top) })
+                .collect(),
+        )
+    }
+
+    /// Catch goals that could never be reached before searching for them
+    fn check(&self, stacks: &[Vec<char>]) -> Result<(), &'static str> {
+        let crates: Vec<char> = stacks.concat();
+        match self {
+            Goal::Layout(target) => {
+                if target.len() != stacks.len() {
+                    return Err("The target has a different number of stacks");
+                }
+                let mut crates = crates;
+                let mut target_crates: Vec<char> = target.concat();
+                crates.sort_unstable();
+                target_crates.sort_unstable();
+                if crates != target_crates {
+                    return Err("The target has different crates to the start");
+                }
+            }
+            Goal::Tops(tops) => {
+                if tops.len() != stacks.len() {
+                    return Err("The target has a different number of stacks");
+                }
+                let count = |items: &mut dyn Iterator<Item = &char>, top| {
+                    items.filter(|&&item| item == top).count()
+                };
+                for &top in tops.iter().flatten() {
+                    if count(&mut tops.iter().flatten(), top) > count(&mut crates.iter(), top) {
+                        return Err("The target needs more crates than there are");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn reached(&self, stacks: &[Vec<char>]) -> bool {
+        match self {
+            Goal::Layout(target) => stacks == target,
+            Goal::Tops(tops) => tops
+                .iter()
+                .zip(stacks)
+                .all(|(top, stack)| top.is_none() || stack.last() == top.as_ref()),
+        }
+    }
+}
+
+enum Search {
+    Found(Vec<Move>),
+    Unreachable,
+    GaveUp,
+}
+
+/// Search for the fewest actions that take the stacks to the goal with this crane,
+/// giving up after seeing `limit` different layouts
+///
+/// If the search gives up on reaching some tops, a longer plan is dug out instead
+/// (see [`dig_for_tops`]). The actions are numbered by the lines they'll be on in
+/// [`puzzle_text`].
+pub fn plan(
+    stacks: &[Vec<char>],
+    goal: &Goal,
+    crane: &dyn Crane,
+    limit: usize,
+) -> Result<Vec<Action>, &'static str> {
+    goal.check(stacks)?;
+
+    let moves = match (search(stacks, goal, crane, limit), goal) {
+        (Search::Found(moves), _) => moves,
+        (Search::Unreachable, _) => return Err("No actions reach the target"),
+        (Search::GaveUp, Goal::Tops(tops)) => {
+            dig_for_tops(stacks, tops, crane).ok_or("Gave up before finding a plan")?
+        }
+        (Search::GaveUp, Goal::Layout(_)) => return Err("Gave up before finding a plan"),
+    };
+
+    // The drawing and a blank line come before the actions
+    let first_line = render(stacks, None).lines().count() + 2;
+    Ok(moves
+        .into_iter()
+        .enumerate()
+        .map(|(i, [amount, from, to])| Action {
+            amount,
+            from,
+            to,
+            line: first_line + i,
+        })
+        .collect())
+}
+
+/// Breadth first, so the first layout to reach the goal has the shortest plan
+fn search(stacks: &[Vec<char>], goal: &Goal, crane: &dyn Crane, limit: usize) -> Search {
+    if goal.reached(stacks) {
+        return Search::Found(Vec::new());
+    }
+
+    // Each layout is kept once, packed into a string shared with `seen`
+    let mut layouts: Vec<Step> = vec![(pack(stacks), None)];
+    let mut seen: FastSet<Rc<str>> = FastSet::default();
+    seen.insert(layouts[0].0.clone());
+
+    let mut next = 0;
+    while next < layouts.len() {
+        let current = unpack(&layouts[next].0);
+        for from in 0..current.len() {
+            for amount in 1..=current[from].len() {
+                for to in (0..current.len()).filter(|&to| to != from) {
+                    let mut after = current.clone();
+                    crane.move_crates(&mut after, amount, from, to);
+                    let key = pack(&after);
+                    if seen.contains(&key) {
+                        continue;
+                    }
+
+                    let step = Some((next, [amount, from + 1, to + 1]));
+                    if goal.reached(&after) {
+                        layouts.push((key, step));
+                        return Search::Found(moves_to(&layouts, layouts.len() - 1));
+                    }
+                    if layouts.len() >= limit {
+                        return Search::GaveUp;
+                    }
+                    seen.insert(key.clone());
+                    layouts.push((key, step));
+                }
+            }
+        }
+        next += 1;
+    }
+
+    Search::Unreachable
+}
+
+fn pack(stacks: &[Vec<char>]) -> Rc<str> {
+    let mut key = String::new();
+    for stack in stacks {
+        key.extend(stack);
+        key.push(STACK_END);
+    }
+    key.into()
+}
+
+fn unpack(key: &str) -> Vec<Vec<char>> {
+    key.split_terminator(STACK_END)
+        .map(|stack| stack.chars().collect())
+        .collect()
+}
+
+/// Follow the layouts back to the start to find the moves that led to this one
+fn moves_to(layouts: &[Step], mut index: usize) -> Vec<Move> {
+    let mut moves = Vec::new();
+    while let Some((previous, step)) = layouts[index].1 {
+        moves.push(step);
+        index = previous;
+    }
+    moves.reverse();
+    moves
+}
+
+/// Reach the tops one stack at a time, digging out the closest crate that's needed
+/// by moving the crates above it onto another stack and back again afterwards
+///
+/// Every move is of one crate, so it works the same for any crane. The plan is far
+/// from the shortest, but it only takes a few moves per crate in the way, so it works
+/// when there are too many layouts to search. It needs at least three stacks.
+fn dig_for_tops(
+    stacks: &[Vec<char>],
+    tops: &[Option<char>],
+    crane: &dyn Crane,
+) -> Option<Vec<Move>> {
+    if stacks.len() < 3 {
+        return None;
+    }
+    let mut stacks = stacks.to_vec();
+    let mut done = vec![false; stacks.len()];
+    let mut moves = Vec::new();
+
+    for (target, top) in tops.iter().enumerate() {
+        let Some(top) = top else {
+            continue;
+        };
+        // Any crate will do except one already on top of a finished stack
+        let (source, depth) = (0..stacks.len())
+            .filter_map(|stack| {
+                let depth = stacks[stack]
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .position(|(depth, item)| item == top && !(done[stack] && depth == 0))?;
+                Some((stack, depth))
+            })
+            .min_by_key(|&(_, depth)| depth)?;
+        let mut others = (0..stacks.len()).filter(|&stack| stack != source && stack != target);
+        let spare = others.next()?;
+        let holder = others.next();
+        let kept = usize::from(depth > 0 && !stacks[spare].is_empty());
+
+        let mut lift = |from: usize, to: usize, times: usize| {
+            for _ in 0..times {
+                crane.move_crates(&mut stacks, 1, from, to);
+                moves.push([1, from + 1, to + 1]);
+            }
+        };
+        if source == target {
+            // The crates in the way go under the top of the spare stack, which is
+            // kept on another stack in the meantime
+            let holder = holder?;
+            lift(spare, holder, kept);
+            lift(source, spare, depth);
+            lift(holder, spare, kept);
+        } else {
+            // The crates in the way go back where they were, in the same order
+            lift(source, spare, depth);
+            lift(source, target, 1);
+            lift(spare, source, depth);
+        }
+        done[target] = true;
+    }
+
+    Some(moves)
+}
+
+/// Write the stacks and actions out the same way as the puzzle input
+pub fn puzzle_text(stacks: &[Vec<char>], actions: &[Action]) -> String {
+    let mut text = render(stacks, None);
+    text.push('\n');
+    for action in actions {
+        text += &format!("{action}\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        crane::{CrateMover9000, CrateMover9001},
+        parse_input, replay,
+    };
+
+    fn start() -> Vec<Vec<char>> {
+        vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+    }
+
+    #[test]
+    fn layout() {
+        // Where the example ends up with the CrateMover 9000
+        let target = vec![vec!['C'], vec!['M'], vec!['P', 'D', 'N', 'Z']];
+        let actions = plan(
+            &start(),
+            &Goal::Layout(target.clone()),
+            &CrateMover9000,
+            10_000,
+        )
+        .unwrap();
+        assert!(actions.len() <= 4);
+
+        // The plan can be read back in and replayed
+        let text = puzzle_text(&start(), &actions);
+        let (mut stacks, parsed) = parse_input(&text).unwrap();
+        assert_eq!(stacks, start());
+        assert_eq!(parsed.len(), actions.len());
+        assert_eq!(parsed[0].line, actions[0].line);
+        replay(&mut stacks, &parsed, &CrateMover9000, |_, _, _| {}).unwrap();
+        assert_eq!(stacks, target);
+    }
+
+    #[test]
+    fn tops() {
+        let actions = plan(&start(), &Goal::tops("..N"), &CrateMover9001, 10_000).unwrap();
+        assert_eq!(
+            puzzle_text(&start(), &actions).lines().last(),
+            Some("move 1 from 1 to 3")
+        );
+
+        // Moving both crates from stack 1 one at a time puts Z on top,
+        // but picking them both up at once doesn't
+        let goal = Goal::tops("..Z");
+        assert_eq!(
+            plan(&start(), &goal, &CrateMover9000, 10_000)
+                .unwrap()
+                .len(),
+            1
+        );
+        let mut stacks = start();
+        let actions = plan(&stacks, &goal, &CrateMover9001, 10_000).unwrap();
+        replay(&mut stacks, &actions, &CrateMover9001, |_, _, _| {}).unwrap();
+        assert_eq!(stacks[2].last(), Some(&'Z'));
+        assert_eq!(actions.len(), 2);
+    }
+
+    #[test]
+    fn shallow_plan_in_a_big_search() {
+        // Nine stacks of six, where A is on top of stack 4 and Q is at the bottom of stack 9
+        let mut letters = "bcdefghijklmnoprstuvwxyzBCDEFGHIJKLMNOPRSTUVWXYZ123456".chars();
+        let mut stacks: Vec<Vec<char>> =
+            (0..9).map(|_| letters.by_ref().take(6).collect()).collect();
+        stacks[3][5] = 'A';
+        stacks[8][0] = 'Q';
+
+        let goal = Goal::tops("A.......Q");
+        let actions = plan(&stacks, &goal, &CrateMover9000, 1_000_000).unwrap();
+        assert_eq!(actions.len(), 2);
+
+        // With hardly any room to search, the tops are dug out instead
+        let actions = plan(&stacks, &goal, &CrateMover9000, 100).unwrap();
+        assert!(actions.len() > 2);
+        replay(&mut stacks, &actions, &CrateMover9000, |_, _, _| {}).unwrap();
+        assert!(goal.reached(&stacks));
+    }
+
+    #[test]
+    fn dig_under_finished_tops() {
+        // Every stack needs a crate from underneath, some from stacks that are already done
+        for tops in ["ZNC", "CZM", "DPZ"] {
+            let goal = Goal::tops(tops);
+            for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+                let mut stacks = start();
+                let actions = plan(&stacks, &goal, crane, 1).unwrap();
+                replay(&mut stacks, &actions, crane, |_, _, _| {}).unwrap();
+                assert!(goal.reached(&stacks), "{tops}: {stacks:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn unreachable() {
+        let missing = vec![vec!['Z'], vec!['M', 'C', 'D'], vec!['P', 'X']];
+        assert_eq!(
+            plan(&start(), &Goal::Layout(missing), &CrateMover9000, 10_000).err(),
+            Some("The target has different crates to the start")
+        );
+        assert_eq!(
+            plan(&start(), &Goal::tops("ZZ."), &CrateMover9000, 100_000).err(),
+            Some("The target needs more crates than there are")
+        );
+        assert_eq!(
+            plan(&[vec!['Z', 'N']], &Goal::tops("Z"), &CrateMover9000, 100).err(),
+            Some("No actions reach the target")
+        );
+
+        let target = vec![vec!['C'], vec!['M'], vec!['P', 'D', 'N', 'Z']];
+        assert_eq!(
+            plan(&start(), &Goal::Layout(target), &CrateMover9000, 2).err(),
+            Some("Gave up before finding a plan")
+        );
+    }
+}
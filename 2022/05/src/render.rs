@@ -0,0 +1,51 @@
+/// Draw stacks the way the puzzle does, with the numbers of the stacks underneath
+///
+/// `moved` is a stack and the number of crates on top of it to draw as `<X>` rather
+/// than `[X]`, to show which crates were just moved there.
+pub fn render(stacks: &[Vec<char>], moved: Option<(usize, usize)>) -> String {
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut output = String::new();
+
+    for level in (0..height).rev() {
+        let row: Vec<String> = stacks
+            .iter()
+            .enumerate()
+            .map(|(i, stack)| match stack.get(level) {
+                Some(item)
+                    if moved
+                        .is_some_and(|(to, amount)| to == i && level + amount >= stack.len()) =>
+                {
+                    format!("<{item}>")
+                }
+                Some(item) => format!("[{item}]"),
+                None => String::from("   "),
+            })
+            .collect();
+        output += row.join(" ").trim_end();
+        output.push('\n');
+    }
+
+    let labels: Vec<String> = (1..=stacks.len()).map(|i| format!("{i:^3}")).collect();
+    output += labels.join(" ").trim_end();
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn puzzle_format() {
+        let stacks = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+        assert_eq!(
+            render(&stacks, None),
+            "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n"
+        );
+        assert_eq!(
+            render(&stacks, Some((1, 2))),
+            "    <D>\n[N] <C>\n[Z] [M] [P]\n 1   2   3\n"
+        );
+        assert_eq!(render(&[vec![], vec!['A']], None), "    [A]\n 1   2\n");
+    }
+}
@@ -1,27 +1,168 @@
-use std::{collections::HashSet, fs};
+use std::{
+    env,
+    fs::File,
+    io::{self, Read},
+};
 
-struct Datastream<'a>(&'a str);
+/// Watches a stream one letter at a time for a window of `size` different letters
+struct MarkerDetector {
+    size: usize,
+    /// The last `size` letters, as a ring buffer
+    window: Vec<u8>,
+    /// How many times each letter is in the window
+    counts: [usize; 256],
+    /// How many different letters are in the window
+    distinct: usize,
+    /// How many letters have been seen so far
+    position: usize,
+}
+
+impl MarkerDetector {
+    fn new(size: usize) -> Self {
+        MarkerDetector {
+            size,
+            window: vec![0; size],
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    /// Add the next letter, returning whether it ends a marker
+    fn push(&mut self, letter: u8) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+
+        let slot = self.position % self.size;
+        if self.position >= self.size {
+            let oldest = self.window[slot] as usize;
+            self.counts[oldest] -= 1;
+            if self.counts[oldest] == 0 {
+                self.distinct -= 1;
+            }
+        }
+
+        self.window[slot] = letter;
+        self.counts[letter as usize] += 1;
+        if self.counts[letter as usize] == 1 {
+            self.distinct += 1;
+        }
+        self.position += 1;
+
+        self.distinct == self.size
+    }
+}
+
+/// Read the whole stream, finding every position (counting letters from 1) where
+/// a marker of each size ends
+///
+/// Whitespace is skipped, so a trailing newline doesn't count as a letter.
+fn markers(mut stream: impl Read, sizes: &[usize]) -> io::Result<Vec<Vec<usize>>> {
+    let mut detectors: Vec<MarkerDetector> = sizes
+        .iter()
+        .map(|&size| MarkerDetector::new(size))
+        .collect();
+    let mut found = vec![Vec::new(); sizes.len()];
+    let mut buffer = [0; 8192];
+
+    loop {
+        let read = match stream.read(&mut buffer) {
+            Ok(0) => return Ok(found),
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
 
-impl Datastream<'_> {
-    fn start_of_packet(&self, unique_after: usize) -> Option<usize> {
-        for i in 0..self.0.len() - unique_after {
-            let buffer: HashSet<char> = self.0[i..i + unique_after].chars().collect();
-            if buffer.len() == unique_after {
-                return Some(i + unique_after);
+        for &letter in buffer[..read].iter().filter(|x| !x.is_ascii_whitespace()) {
+            for (detector, found) in detectors.iter_mut().zip(&mut found) {
+                if detector.push(letter) {
+                    found.push(detector.position);
+                }
             }
         }
-        None
     }
 }
 
 fn main() {
-    let input = fs::read_to_string("input.txt").unwrap();
+    // Read the datastream from a file other than input.txt with `--input <file>`,
+    // and list every marker instead of just the first with `--all`
+    let args: Vec<String> = env::args().collect();
+    let path = args
+        .iter()
+        .position(|arg| arg == "--input")
+        .map_or("input.txt", |i| &args[i + 1]);
+    let show_all = args.iter().any(|arg| arg == "--all");
 
-    let ds = Datastream(input.trim());
+    let file = File::open(path).expect("Couldn't open the datastream");
+    let found = markers(file, &[4, 14]).expect("Couldn't read the datastream");
 
-    let start = ds.start_of_packet(4).unwrap();
-    println!("The start of the packet is at {start}");
+    for (name, positions) in ["packet", "message"].iter().zip(&found) {
+        match positions.first() {
+            Some(start) => println!("The start of the {name} is at {start}"),
+            None => println!("There is no start of {name} marker"),
+        }
+        if show_all {
+            println!("Every start of {name} marker: {positions:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    /// Hands out one byte per read, to check markers split across reads
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((&byte, rest)) if !buf.is_empty() => {
+                    buf[0] = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    fn first(input: &str, size: usize) -> Option<usize> {
+        markers(input.as_bytes(), &[size]).unwrap()[0]
+            .first()
+            .copied()
+    }
 
-    let start_of_message = ds.start_of_packet(14).unwrap();
-    println!("The start of the message is at {start_of_message}");
+    #[test]
+    fn example() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (input, packet, message) in examples {
+            assert_eq!(first(input, 4), Some(packet));
+            assert_eq!(first(input, 14), Some(message));
+            assert_eq!(
+                markers(Trickle(input.as_bytes()), &[4, 14]).unwrap()[1][0],
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn every_marker() {
+        assert_eq!(
+            markers("abcdabbcde\n".as_bytes(), &[4]).unwrap(),
+            vec![vec![4, 5, 6, 10]]
+        );
+        // The very last window counts
+        assert_eq!(first("aaaabcd", 4), Some(7));
+        // Too short to ever have a marker
+        assert_eq!(first("abc", 4), None);
+        assert_eq!(first("", 14), None);
+    }
 }
@@ -1,5 +1,7 @@
 use std::{cell::RefCell, collections::HashMap, fs, rc::Rc};
 
+use aoc_common::parse::{numbers, sections};
+
 #[derive(Debug)]
 enum Operation {
     Add(OpNumber),
@@ -139,22 +141,13 @@ fn test_part_2() {
 fn parse_input(file_name: &str) -> Vec<Monkey> {
     let input = fs::read_to_string(file_name).unwrap();
 
-    input
-        .trim()
-        .split("\n\n")
+    sections(&input)
+        .into_iter()
         .map(|monkey| {
-            let mut monkey = monkey.lines().skip(1);
+            let mut monkey = monkey.into_iter().skip(1);
 
             Monkey {
-                items: Rc::new(RefCell::new(
-                    monkey
-                        .next()
-                        .unwrap()
-                        .replace("  Starting items: ", "")
-                        .split(", ")
-                        .map(|n| n.parse().unwrap())
-                        .collect::<Vec<usize>>(),
-                )),
+                items: Rc::new(RefCell::new(numbers(monkey.next().unwrap()))),
 
                 op: monkey
                     .next()
@@ -165,26 +158,9 @@ fn parse_input(file_name: &str) -> Vec<Monkey> {
                     .try_into()
                     .unwrap(),
 
-                divisible_test: monkey
-                    .next()
-                    .unwrap()
-                    .replace("  Test: divisible by ", "")
-                    .parse()
-                    .unwrap(),
-
-                if_true: monkey
-                    .next()
-                    .unwrap()
-                    .replace("    If true: throw to monkey ", "")
-                    .parse()
-                    .unwrap(),
-
-                if_false: monkey
-                    .next()
-                    .unwrap()
-                    .replace("    If false: throw to monkey ", "")
-                    .parse()
-                    .unwrap(),
+                divisible_test: numbers(monkey.next().unwrap())[0],
+                if_true: numbers(monkey.next().unwrap())[0],
+                if_false: numbers(monkey.next().unwrap())[0],
             }
         })
         .collect()
@@ -1,5 +1,6 @@
 use std::{cmp::Ordering, fs};
 
+use aoc_common::parse::sections;
 use serde::Deserialize;
 
 // serde op frfr
@@ -152,10 +153,8 @@ fn test_part_2() {
 fn parse_input(file_name: &str) -> Vec<(Value, Value)> {
     let input = fs::read_to_string(file_name).unwrap();
 
-    input
-        .trim()
-        .split("\n\n")
-        .map(|pair| pair.split_once('\n').unwrap())
-        .map(|(l, r)| (l.into(), r.into()))
+    sections(&input)
+        .into_iter()
+        .map(|pair| (pair[0].into(), pair[1].into()))
         .collect()
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fmt, fs};
+use std::{fmt, fs};
+
+use aoc_common::hash::FastMap;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct Position {
@@ -33,7 +35,7 @@ enum Tile {
 }
 
 struct Scan {
-    map: HashMap<Position, Tile>,
+    map: FastMap<Position, Tile>,
     max_y: usize,
     floor: Option<usize>,
 }
@@ -69,7 +71,7 @@ impl Scan {
     /// Some if it settled, or None if it fell off the map (or can't move from the source)
     fn drop_sand(&self, from: Position) -> Option<Position> {
         // If the source tile is blocked
-        if self.map.get(&from).is_some() {
+        if self.map.contains_key(&from) {
             return None;
         }
 
@@ -146,12 +148,11 @@ fn parse_input(file_name: &str, has_floor: bool) -> Scan {
     let walls: Vec<Vec<Position>> = input
         .trim()
         .lines()
-        .into_iter()
         .map(|line| line.split(" -> ").map(|p| p.into()).collect())
         .collect();
 
     // Loop through all the walls and fill with rock
-    let mut map = HashMap::new();
+    let mut map = FastMap::default();
     for wall in walls {
         for window in wall.windows(2) {
             let (mut pos, target) = (window[0], window[1]);
@@ -175,6 +176,10 @@ fn parse_input(file_name: &str, has_floor: bool) -> Scan {
 
 #[cfg(test)]
 mod test {
+    use std::path::Path;
+
+    use aoc_common::bench;
+
     use crate::*;
 
     #[test]
@@ -190,4 +195,19 @@ mod test {
         let resting = scan.simulate_sand(Position { x: 500, y: 0 });
         assert_eq!(resting, 93);
     }
+
+    #[test]
+    #[ignore]
+    fn timing() {
+        let file = if Path::new("input.txt").exists() {
+            "input.txt"
+        } else {
+            "test.txt"
+        };
+        bench::time("2022/14 both parts", || {
+            let abyss = parse_input(file, false).simulate_sand(Position { x: 500, y: 0 });
+            let floor = parse_input(file, true).simulate_sand(Position { x: 500, y: 0 });
+            (abyss, floor)
+        });
+    }
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fs};
+use std::fs;
+
+use aoc_common::hash::FastMap;
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 struct Position {
@@ -32,11 +34,11 @@ impl TryFrom<char> for Character {
     }
 }
 
-struct Schematic(HashMap<Position, Character>);
+struct Schematic(FastMap<Position, Character>);
 
 impl From<&str> for Schematic {
     fn from(value: &str) -> Self {
-        let mut schematic = HashMap::new();
+        let mut schematic = FastMap::default();
         value.lines().enumerate().for_each(|(y, line)| {
             line.trim().char_indices().for_each(|(x, char)| {
                 if let Ok(character) = Character::try_from(char) {
@@ -180,6 +182,8 @@ fn main() {
 
 #[cfg(test)]
 mod test {
+    use aoc_common::bench;
+
     use crate::*;
 
     const TEST_INPUT: &str = "467..114..
@@ -206,4 +210,14 @@ mod test {
 
         assert_eq!(schematic.sum_gear_ratios(), 467835);
     }
+
+    #[test]
+    #[ignore]
+    fn timing() {
+        let input = bench::input(TEST_INPUT);
+        bench::time("2023/03 both parts", || {
+            let schematic = Schematic::from(input.as_str());
+            (schematic.sum_part_numbers(), schematic.sum_gear_ratios())
+        });
+    }
 }
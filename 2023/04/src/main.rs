@@ -1,5 +1,7 @@
 use std::fs;
 
+use aoc_common::parse::numbers;
+
 struct Stack(Vec<Card>);
 
 struct Card {
@@ -15,13 +17,7 @@ impl From<&str> for Stack {
 
 impl From<&str> for Card {
     fn from(value: &str) -> Self {
-        let mut groups = value.split_once(':').unwrap().1.split('|').map(|numbers| {
-            numbers
-                .trim()
-                .split_ascii_whitespace()
-                .map(|number| number.parse().expect("valid number"))
-                .collect()
-        });
+        let mut groups = value.split_once(':').unwrap().1.split('|').map(numbers);
 
         Self {
             winning_numbers: groups.next().unwrap(),
@@ -1,5 +1,6 @@
 use std::fs;
 
+use aoc_common::parse::{numbers, record, sections};
 use tqdm::Iter;
 
 struct Range {
@@ -23,34 +24,22 @@ impl FromIterator<Range> for Map {
 
 impl From<String> for Almanac {
     fn from(value: String) -> Self {
-        let seeds = value
-            .lines()
-            .next()
-            .unwrap()
-            .split_once(':')
-            .unwrap()
-            .1
-            .trim()
-            .split_ascii_whitespace()
-            .map(|seed| seed.parse().unwrap())
-            .collect();
+        let seeds = numbers(value.lines().next().unwrap());
 
-        let maps = value
-            .split("\n\n")
+        let maps = sections(&value)
+            .into_iter()
             .skip(1)
             .map(|category| {
                 category
-                    .lines()
+                    .into_iter()
                     .skip(1)
                     .map(|map_str| {
-                        let mut numbers = map_str
-                            .trim()
-                            .split_ascii_whitespace()
-                            .map(|number| number.parse().unwrap());
+                        let [destination, source, length] =
+                            record(map_str).expect("Range should have 3 numbers");
                         Range {
-                            destination: numbers.next().unwrap(),
-                            source: numbers.next().unwrap(),
-                            length: numbers.next().unwrap(),
+                            destination,
+                            source,
+                            length,
                         }
                     })
                     .collect()
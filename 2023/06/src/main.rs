@@ -1,5 +1,7 @@
 use std::fs;
 
+use aoc_common::parse::numbers;
+
 struct Race {
     duration: usize,
     record: usize,
@@ -10,18 +12,7 @@ fn distance_travelled(speed: usize, duration: usize) -> usize {
 }
 
 fn parse_races(value: String) -> Vec<Race> {
-    let numbers: Vec<Vec<usize>> = value
-        .lines()
-        .map(|line| {
-            line.split_once(':')
-                .unwrap()
-                .1
-                .trim()
-                .split_ascii_whitespace()
-                .map(|number| number.parse().unwrap())
-                .collect()
-        })
-        .collect();
+    let numbers: Vec<Vec<usize>> = value.lines().map(numbers).collect();
 
     numbers
         .first()
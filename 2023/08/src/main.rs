@@ -1,8 +1,12 @@
-use std::{collections::HashMap, fs};
+use std::fs;
+
+use aoc_common::hash::Interner;
 
 struct Map {
     instructions: String,
-    nodes: HashMap<String, (String, String)>,
+    names: Interner<String>,
+    /// The (left, right) node ids for each node id
+    nodes: Vec<(usize, usize)>,
 }
 
 impl From<String> for Map {
@@ -12,17 +16,31 @@ impl From<String> for Map {
         let instructions = lines.next().unwrap().to_owned();
         lines.next().unwrap();
 
-        let nodes = HashMap::from_iter(lines.map(|line| {
+        let mut names = Interner::new();
+        let mut edges = Vec::new();
+        for line in lines {
             let (from, to) = line.trim().split_once(" = ").unwrap();
             let (left, right) = to
                 .trim_matches(|c| c == '(' || c == ')')
                 .split_once(", ")
                 .unwrap();
-            (from.to_owned(), (left.to_owned(), right.to_owned()))
-        }));
+            edges.push((
+                names.intern(from.to_owned()),
+                (
+                    names.intern(left.to_owned()),
+                    names.intern(right.to_owned()),
+                ),
+            ));
+        }
+
+        let mut nodes = vec![(0, 0); names.len()];
+        for (from, node) in edges {
+            nodes[from] = node;
+        }
 
         Self {
             instructions,
+            names,
             nodes,
         }
     }
@@ -46,18 +64,15 @@ impl Map {
     /// Steps from a starting location to any location ending with a string
     fn steps(&self, start: &str, end: &str) -> usize {
         let mut steps = 0;
-        let mut location = String::from(start);
+        let mut location = self.names.get(start).unwrap();
+        let is_end: Vec<bool> = self.names.iter().map(|name| name.ends_with(end)).collect();
 
         for direction in self.instructions.chars().cycle() {
-            let node = self.nodes.get(&location).unwrap();
-            location = if direction == 'L' {
-                node.0.clone()
-            } else {
-                node.1.clone()
-            };
+            let node = self.nodes[location];
+            location = if direction == 'L' { node.0 } else { node.1 };
             steps += 1;
 
-            if location.ends_with(end) {
+            if is_end[location] {
                 break;
             }
         }
@@ -66,9 +81,9 @@ impl Map {
     }
 
     fn ghost_steps(&self) -> usize {
-        self.nodes
-            .keys()
-            .filter(|key| key.ends_with('A'))
+        self.names
+            .iter()
+            .filter(|name| name.ends_with('A'))
             .map(|start| self.steps(start, "Z"))
             .reduce(lcm) // Find the least common multiple of all distances
             .unwrap()
@@ -88,8 +103,54 @@ fn main() {
 
 #[cfg(test)]
 mod test {
+    use aoc_common::{bench, hash::FastMap};
+
     use crate::*;
 
+    /// Both parts the way they were before names were interned, looking up every
+    /// step by name, so `timing` has a baseline that the hasher matters to
+    fn walk_by_name(input: &str) -> (usize, usize) {
+        let mut lines = input.lines();
+        let instructions = lines.next().unwrap();
+        lines.next().unwrap();
+        let nodes: FastMap<String, (String, String)> = lines
+            .map(|line| {
+                let (from, to) = line.trim().split_once(" = ").unwrap();
+                let (left, right) = to
+                    .trim_matches(|c| c == '(' || c == ')')
+                    .split_once(", ")
+                    .unwrap();
+                (from.to_owned(), (left.to_owned(), right.to_owned()))
+            })
+            .collect();
+
+        let steps = |start: &str, end: &str| {
+            let mut location = String::from(start);
+            let mut steps = 0;
+            for direction in instructions.chars().cycle() {
+                let node = &nodes[&location];
+                location = if direction == 'L' {
+                    node.0.clone()
+                } else {
+                    node.1.clone()
+                };
+                steps += 1;
+                if location.ends_with(end) {
+                    break;
+                }
+            }
+            steps
+        };
+        let ghost_steps = nodes
+            .keys()
+            .filter(|name| name.ends_with('A'))
+            .map(|start| steps(start, "Z"))
+            .reduce(lcm)
+            .unwrap();
+
+        (steps("AAA", "ZZZ"), ghost_steps)
+    }
+
     #[test]
     fn part_1_2_steps() {
         let input = String::from(
@@ -137,4 +198,26 @@ mod test {
         let map = Map::from(input);
         assert_eq!(map.ghost_steps(), 6);
     }
+
+    #[test]
+    #[ignore]
+    fn timing() {
+        let input = bench::input(
+            "LLR
+
+            AAA = (BBB, BBB)
+            BBB = (AAA, ZZZ)
+            ZZZ = (ZZZ, ZZZ)",
+        );
+        let both_parts = || {
+            let map = Map::from(input.clone());
+            (map.steps("AAA", "ZZZ"), map.ghost_steps())
+        };
+        assert_eq!(both_parts(), walk_by_name(&input));
+
+        // The interned map only hashes names while parsing, so the hasher makes more
+        // of a difference to walking by name
+        bench::time("2023/08 both parts", both_parts);
+        bench::time("2023/08 walk by name", || walk_by_name(&input));
+    }
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fs};
+use std::fs;
+
+use aoc_common::hash::FastMap;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Direction {
@@ -91,7 +93,7 @@ impl Position {
 }
 
 struct Field {
-    tiles: HashMap<Position, Pipe>,
+    tiles: FastMap<Position, Pipe>,
     height: usize,
     width: usize,
     start_pos: Position,
@@ -99,7 +101,7 @@ struct Field {
 
 impl From<&str> for Field {
     fn from(value: &str) -> Self {
-        let mut tiles = HashMap::new();
+        let mut tiles = FastMap::default();
         let mut start_pos = None;
 
         for (y, line) in value.lines().enumerate() {
@@ -218,6 +220,8 @@ fn main() {
 
 #[cfg(test)]
 mod test {
+    use aoc_common::bench;
+
     use super::*;
 
     #[test]
@@ -291,4 +295,27 @@ mod test {
         field.infer_start();
         assert_eq!(field.count_enclosed(field.calculate_loop()), 10)
     }
+
+    #[test]
+    #[ignore]
+    fn timing() {
+        let input = bench::input(
+            ".F----7F7F7F7F-7....
+        .|F--7||||||||FJ....
+        .||.FJ||||||||L7....
+        FJL7L7LJLJ||LJ.L-7..
+        L--J.L7...LJS7F-7L7.
+        ....F-J..F7FJ|L7L7L7
+        ....L7.F7||L7|.L7L7|
+        .....|FJLJ|FJ|F7|.LJ
+        ....FJL-7.||.||||...
+        ....L---J.LJ.LJLJ...",
+        );
+        bench::time("2023/10 both parts", || {
+            let mut field = Field::from(input.as_str());
+            field.infer_start();
+            let pipes = field.calculate_loop();
+            (pipes.len() / 2, field.count_enclosed(pipes))
+        });
+    }
 }
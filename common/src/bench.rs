@@ -0,0 +1,42 @@
+//! Timing for days that use [`crate::hash`], to compare hashers on their real code
+//!
+//! Each of those days has an ignored `timing` test, so compare
+//! `cargo test --release timing -- --ignored --nocapture` with the same command
+//! plus `--features siphash`.
+
+use std::{
+    fs,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Which hasher [`crate::hash::FastMap`] is built with
+pub const HASHER: &str = if cfg!(feature = "siphash") {
+    "SipHash"
+} else {
+    "FxHash"
+};
+
+/// How long to keep repeating a workload for
+const BUDGET: Duration = Duration::from_secs(1);
+
+/// The day's real input if there is one, otherwise its example
+pub fn input(example: &str) -> String {
+    fs::read_to_string("input.txt").unwrap_or_else(|_| example.to_owned())
+}
+
+/// Run a workload over and over for about a second, printing and returning the fastest run
+pub fn time<T>(name: &str, mut workload: impl FnMut() -> T) -> Duration {
+    let started = Instant::now();
+    let mut fastest = Duration::MAX;
+    let mut runs = 0;
+    while runs < 3 || started.elapsed() < BUDGET {
+        let start = Instant::now();
+        black_box(workload());
+        fastest = fastest.min(start.elapsed());
+        runs += 1;
+    }
+
+    println!("{name:<24} {HASHER:<8} {fastest:>10.2?} (fastest of {runs})");
+    fastest
+}
@@ -0,0 +1,169 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// A `HashMap` using [`FxHasher`], for small keys like positions in hot loops
+pub type FastMap<K, V> = HashMap<K, V, FastHasher>;
+
+/// A `HashSet` using [`FxHasher`]
+pub type FastSet<T> = HashSet<T, FastHasher>;
+
+/// Builds the hasher for [`FastMap`] and [`FastSet`]
+#[cfg(not(feature = "siphash"))]
+pub type FastHasher = std::hash::BuildHasherDefault<FxHasher>;
+
+/// With the `siphash` feature, the maps go back to the default hasher, so days can
+/// be timed against how they were before
+#[cfg(feature = "siphash")]
+pub type FastHasher = std::collections::hash_map::RandomState;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The hashing algorithm used by rustc, which is much faster than the default
+/// SipHash but makes no attempt to resist collision attacks (we trust our input)
+#[derive(Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            let mut word = [0; 8];
+            word[..remainder.len()].copy_from_slice(remainder);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Gives each distinct value a small, dense id so it can be used to index a `Vec`
+/// instead of being hashed over and over
+#[derive(Clone, Debug)]
+pub struct Interner<T> {
+    ids: FastMap<T, usize>,
+    values: Vec<T>,
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            ids: FastMap::default(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Interner<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the id of a value, giving it the next id if it hasn't been seen before
+    pub fn intern(&mut self, value: T) -> usize {
+        if let Some(&id) = self.ids.get(&value) {
+            return id;
+        }
+        let id = self.values.len();
+        self.ids.insert(value.clone(), id);
+        self.values.push(value);
+        id
+    }
+
+    /// Get the id of a value, if it has been interned
+    pub fn get<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(value).copied()
+    }
+
+    /// Look up the value with this id
+    pub fn resolve(&self, id: usize) -> &T {
+        &self.values[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Every value, in order of their ids
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fast_map() {
+        let mut map: FastMap<(isize, isize), usize> = FastMap::default();
+        for x in -50..50 {
+            for y in -50..50 {
+                *map.entry((x, y)).or_default() += 1;
+            }
+        }
+        *map.entry((0, 0)).or_default() += 1;
+
+        assert_eq!(map.len(), 100 * 100);
+        assert_eq!(map[&(0, 0)], 2);
+        assert_eq!(map[&(-50, 49)], 1);
+    }
+
+    #[test]
+    fn interner() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.intern(String::from("AAA")), 0);
+        assert_eq!(interner.intern(String::from("BBB")), 1);
+        assert_eq!(interner.intern(String::from("AAA")), 0);
+
+        assert_eq!(interner.get("BBB"), Some(1));
+        assert_eq!(interner.get("ZZZ"), None);
+        assert_eq!(interner.resolve(1), "BBB");
+        assert_eq!(interner.len(), 2);
+    }
+}
@@ -0,0 +1,115 @@
+use std::{fmt::Debug, str::FromStr};
+
+/// Extract every integer from some text, ignoring any words around them
+///
+/// A `-` is treated as a sign when it directly precedes a digit and doesn't
+/// follow a letter or digit, so `x=-3` gives `-3` but `2-4` gives `2` and `4`
+///
+/// Only for trusted input, as it panics if a number doesn't fit in `T` (such as a
+/// negative number for `usize`). Use [`try_numbers`] otherwise.
+pub fn numbers<T>(text: &str) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    try_numbers(text).expect("Failed to parse number")
+}
+
+/// Extract every integer like [`numbers`], or the error from the first one that
+/// doesn't fit in `T`
+pub fn try_numbers<T: FromStr>(text: &str) -> Result<Vec<T>, T::Err> {
+    let bytes = text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+
+        if negative || bytes[i].is_ascii_digit() {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            numbers.push(text[start..i].parse()?);
+        } else {
+            i += 1;
+        }
+    }
+
+    Ok(numbers)
+}
+
+/// Extract exactly `N` integers from a line with a fixed shape,
+/// such as `move 1 from 2 to 1`, or `None` if there are more or fewer,
+/// or any of them don't fit in `T`
+pub fn record<T: FromStr, const N: usize>(line: &str) -> Option<[T; N]> {
+    try_numbers(line).ok()?.try_into().ok()
+}
+
+/// Split text into sections separated by blank lines, returning the lines of each
+///
+/// Works with CRLF line endings, and lines containing only whitespace count as blank
+pub fn sections(text: &str) -> Vec<Vec<&str>> {
+    let mut sections = vec![Vec::new()];
+
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            if !sections.last().unwrap().is_empty() {
+                sections.push(Vec::new());
+            }
+        } else {
+            sections.last_mut().unwrap().push(line);
+        }
+    }
+
+    if sections.last().unwrap().is_empty() {
+        sections.pop();
+    }
+    sections
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numbers_in_text() {
+        assert_eq!(numbers::<usize>("move 12 from 3 to 9"), vec![12, 3, 9]);
+        assert_eq!(
+            numbers::<isize>("Sensor at x=-2, y=15: closest beacon is at x=10, y=-16"),
+            vec![-2, 15, 10, -16]
+        );
+        assert_eq!(numbers::<usize>("2-4,6-8"), vec![2, 4, 6, 8]);
+        assert_eq!(numbers::<isize>("0 3 -6 - 9"), vec![0, 3, -6, 9]);
+        assert!(numbers::<usize>("no numbers here").is_empty());
+    }
+
+    #[test]
+    fn fixed_records() {
+        assert_eq!(record::<usize, 3>("move 1 from 2 to 1"), Some([1, 2, 1]));
+        assert_eq!(record::<usize, 3>("50 98 2"), Some([50, 98, 2]));
+        assert_eq!(record::<usize, 3>("move 1 from 2"), None);
+        assert_eq!(record::<usize, 3>("move -1 from 2 to 1"), None);
+        assert_eq!(record::<u8, 2>("300 1"), None);
+        assert_eq!(try_numbers::<isize>("x=-3"), Ok(vec![-3]));
+        assert!(try_numbers::<usize>("x=-3").is_err());
+    }
+
+    #[test]
+    fn blank_line_sections() {
+        let expected = vec![vec!["1000", "2000"], vec!["4000"], vec!["5000", "6000"]];
+
+        assert_eq!(sections("1000\n2000\n\n4000\n\n5000\n6000"), expected);
+        assert_eq!(
+            sections("1000\r\n2000\r\n\r\n4000\r\n\r\n5000\r\n6000\r\n"),
+            expected
+        );
+        assert_eq!(
+            sections("\n1000 \n2000\n  \n\n4000\n\t\n5000\n6000\n\n"),
+            expected
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::{env, fs, path::PathBuf, process};
+
+mod report;
+mod submit;
+
+const USAGE: &str = "Usage:
+    aoc_tools report [--run] [--output <file>] [--root <dir>]
+    aoc_tools submit <year> <day> <level> <answer> [--session <token>] [--root <dir>]
+
+The session token can also be set with AOC_SESSION or saved in .aoc/session";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("report") => report(&args[1..]),
+        Some("submit") => submit(&args[1..]),
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(1);
+        }
+    }
+}
+
+/// The repo root, which is the parent of this crate unless overridden
+fn root(args: &[String]) -> PathBuf {
+    flag_value(args, "--root").map_or_else(
+        || PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(".."),
+        PathBuf::from,
+    )
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn report(args: &[String]) {
+    let root = root(args);
+    let mut days = report::scan(&root);
+
+    if args.iter().any(|arg| arg == "--run") {
+        report::run_all(&root, &mut days);
+    }
+
+    let markdown = report::render(&days);
+    match flag_value(args, "--output") {
+        Some(file) => fs::write(file, markdown).expect("Failed to write report"),
+        None => print!("{markdown}"),
+    }
+}
+
+fn submit(args: &[String]) {
+    let root = root(args);
+
+    let (Some(year), Some(day), Some(level), Some(answer)) = (
+        args.first().and_then(|year| year.parse().ok()),
+        args.get(1).and_then(|day| day.parse().ok()),
+        args.get(2).and_then(|level| level.parse().ok()),
+        args.get(3),
+    ) else {
+        eprintln!("{USAGE}");
+        process::exit(1);
+    };
+
+    let session = flag_value(args, "--session")
+        .map(String::from)
+        .or_else(|| env::var("AOC_SESSION").ok())
+        .or_else(|| fs::read_to_string(root.join(".aoc/session")).ok())
+        .expect("No session token provided");
+
+    let mut history = submit::History::load(&root);
+    match submit::Client::new(&session).submit(&mut history, year, day, level, answer) {
+        Ok(outcome) => println!("{outcome}"),
+        Err(error) => {
+            eprintln!("{error}");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,526 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use crate::submit::History;
+
+/// Where the results of previous `--run` reports are kept, relative to the repo root
+const RUNS_FILE: &str = ".aoc/runs.tsv";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Language {
+    Rust,
+    TypeScript,
+    JavaScript,
+    Python,
+}
+
+impl Language {
+    fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "rs" => Some(Self::Rust),
+            "ts" => Some(Self::TypeScript),
+            "js" => Some(Self::JavaScript),
+            "py" => Some(Self::Python),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Rust => "Rust",
+                Self::TypeScript => "TypeScript",
+                Self::JavaScript => "JavaScript",
+                Self::Python => "Python",
+            }
+        )
+    }
+}
+
+/// The outcome of actually running a day's tests and solution
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Run {
+    passed: bool,
+    runtime: Option<Duration>,
+    /// How many different lines the solution printed, up to one for each part
+    answers: usize,
+}
+
+#[derive(Debug)]
+pub struct Day {
+    year: u16,
+    day: u8,
+    path: PathBuf,
+    languages: Vec<Language>,
+    /// Whether there's more than the `cargo new` stub
+    started: bool,
+    /// How many parts have had a correct answer submitted, if anything has been submitted
+    stars: Option<usize>,
+    /// How many parts have code or tests named after them, like `part_1`
+    named_parts: usize,
+    tests: usize,
+    last_run: Option<Run>,
+}
+
+impl Day {
+    /// Inspect a day's directory and work out how far along it is
+    fn scan(year: u16, day: u8, path: PathBuf) -> Self {
+        let mut languages = Vec::new();
+        let mut tests = 0;
+        let mut started = false;
+        let mut named = [false; 2];
+
+        for file in source_files(&path) {
+            let Some(language) = file
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(Language::from_extension)
+            else {
+                continue;
+            };
+            if !languages.contains(&language) {
+                languages.push(language);
+            }
+
+            let source = fs::read_to_string(&file).unwrap_or_default();
+            tests += count_tests(&source, language);
+            for (part, named) in named.iter_mut().enumerate() {
+                *named |= names_part(&source, part + 1);
+            }
+            if is_entrypoint(&file, language) {
+                started |= !is_stub(&source);
+            }
+        }
+        languages.sort();
+
+        Self {
+            year,
+            day,
+            path,
+            languages,
+            started,
+            stars: None,
+            named_parts: named.iter().filter(|&&named| named).count(),
+            tests,
+            last_run: None,
+        }
+    }
+
+    /// How many parts are solved, going by the answers submitted for the day if there
+    /// are any, and otherwise by what the code names and what a passing run printed
+    fn parts(&self) -> usize {
+        if let Some(stars) = self.stars {
+            return stars;
+        }
+        match self.last_run {
+            Some(run) if !run.passed => 0,
+            Some(run) => run.answers.max(self.named_parts),
+            None => self.named_parts,
+        }
+    }
+
+    /// How far along the day is, leaving it "in progress" when nothing says which
+    /// parts are solved
+    fn status(&self) -> &'static str {
+        match (self.last_run, self.started, self.parts()) {
+            (Some(run), _, _) if !run.passed => "failing",
+            (_, _, 2..) => "complete",
+            (_, _, 1) => "part 1",
+            (_, false, _) => "not started",
+            (_, true, 0) => "in progress",
+        }
+    }
+
+    /// Run the tests and time the solution against `input.txt` if there is one
+    fn run(&mut self) {
+        let Some(language) = self.languages.first() else {
+            return;
+        };
+
+        let (test, solve): (Option<Vec<&str>>, Vec<&str>) = match language {
+            Language::Rust => (
+                Some(vec!["cargo", "test", "--quiet"]),
+                vec!["cargo", "run", "--release", "--quiet"],
+            ),
+            Language::TypeScript => (None, vec!["npx", "ts-node", "main.ts"]),
+            Language::JavaScript => (None, vec!["node", "main.js"]),
+            Language::Python => (None, vec!["python3", "main.py"]),
+        };
+
+        let mut passed = test.is_none_or(|test| self.execute(&test).is_some());
+
+        let (runtime, answers) = if self.path.join("input.txt").exists() {
+            // Run once to get any compilation out of the way, then time the second run
+            passed &= self.execute(&solve).is_some();
+            self.execute(&solve)
+                .map_or((None, 0), |(runtime, printed)| {
+                    (Some(runtime), count_answers(&printed))
+                })
+        } else {
+            (None, 0)
+        };
+
+        self.last_run = Some(Run {
+            passed,
+            runtime,
+            answers,
+        });
+    }
+
+    /// Run a command in the day's directory, returning how long it took and what it
+    /// printed if it succeeded
+    fn execute(&self, command: &[&str]) -> Option<(Duration, String)> {
+        let start = Instant::now();
+        let output = Command::new(command[0])
+            .args(&command[1..])
+            .current_dir(&self.path)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let printed = String::from_utf8_lossy(&output.stdout).into_owned();
+        output.status.success().then(|| (start.elapsed(), printed))
+    }
+}
+
+/// Find every year/day folder in the repo
+pub fn scan(root: &Path) -> Vec<Day> {
+    let runs = load_runs(root);
+    let history = History::load(root);
+
+    let mut days: Vec<Day> = numbered_dirs(root, 4)
+        .into_iter()
+        .flat_map(|(year, year_path)| {
+            numbered_dirs(&year_path, 2)
+                .into_iter()
+                .map(move |(day, path)| Day::scan(year as u16, day as u8, path))
+        })
+        .map(|mut day| {
+            day.last_run = runs.get(&(day.year, day.day)).copied();
+            day.stars = history
+                .submitted(day.year, day.day)
+                .then(|| history.stars(day.year, day.day));
+            day
+        })
+        .collect();
+
+    days.sort_by_key(|day| (day.year, day.day));
+    days
+}
+
+/// Run every day and save the results so later reports can show them
+pub fn run_all(root: &Path, days: &mut [Day]) {
+    for day in days.iter_mut() {
+        eprintln!("Running {}/{:02}...", day.year, day.day);
+        day.run();
+    }
+    save_runs(root, days);
+}
+
+/// Generate a markdown table for each year
+pub fn render(days: &[Day]) -> String {
+    let mut markdown = String::from("# Advent of Code progress\n");
+
+    let mut years: Vec<u16> = days.iter().map(|day| day.year).collect();
+    years.dedup();
+
+    for year in years {
+        let days: Vec<&Day> = days.iter().filter(|day| day.year == year).collect();
+        let stars: usize = days.iter().map(|day| day.parts()).sum();
+
+        markdown += &format!("\n## {year} ({stars}/50 stars)\n\n");
+        markdown += "| Day | Status | Languages | Tests | Runtime |\n";
+        markdown += "| --: | ------ | --------- | ----: | ------: |\n";
+
+        for day in days {
+            let languages: Vec<String> = day.languages.iter().map(|l| l.to_string()).collect();
+            let runtime = day
+                .last_run
+                .and_then(|run| run.runtime)
+                .map_or(String::from("-"), format_duration);
+            markdown += &format!(
+                "| [{:02}]({}/{:02}) | {} | {} | {} | {} |\n",
+                day.day,
+                day.year,
+                day.day,
+                day.status(),
+                languages.join(", "),
+                day.tests,
+                runtime,
+            );
+        }
+    }
+
+    markdown
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration.as_secs() > 0 {
+        format!("{:.2} s", duration.as_secs_f64())
+    } else {
+        format!("{} ms", duration.as_millis())
+    }
+}
+
+/// Subdirectories whose names are entirely made up of `digits` digits, with their number
+fn numbered_dirs(path: &Path, digits: usize) -> Vec<(usize, PathBuf)> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if name.len() != digits || !name.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            Some((name.parse().ok()?, entry.path()))
+        })
+        .collect()
+}
+
+/// Every file in a day's directory, skipping build output and dependencies
+fn source_files(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .flat_map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                match entry.file_name().to_str() {
+                    Some("target" | "node_modules") => Vec::new(),
+                    _ => source_files(&path),
+                }
+            } else {
+                vec![path]
+            }
+        })
+        .collect()
+}
+
+fn is_entrypoint(file: &Path, language: Language) -> bool {
+    let name = file.file_name().and_then(|name| name.to_str());
+    match language {
+        Language::Rust => name == Some("main.rs"),
+        _ => file.file_stem().and_then(|stem| stem.to_str()) == Some("main"),
+    }
+}
+
+fn count_tests(source: &str, language: Language) -> usize {
+    match language {
+        Language::Rust => source.matches("#[test]").count(),
+        Language::Python => source.matches("def test_").count(),
+        Language::TypeScript | Language::JavaScript => source
+            .lines()
+            .map(str::trim_start)
+            .filter(|line| line.starts_with("test(") || line.starts_with("it("))
+            .count(),
+    }
+}
+
+/// Whether a function or test is named after this part, like `part_1` or `part1`
+fn names_part(source: &str, part: usize) -> bool {
+    source.contains(&format!("part_{part}")) || source.contains(&format!("part{part}"))
+}
+
+/// How many parts a solution seems to answer, from the different lines it printed
+fn count_answers(printed: &str) -> usize {
+    let mut lines: Vec<&str> = printed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines.len().min(2)
+}
+
+/// Whether the entrypoint is still the placeholder that `cargo new` and friends create
+fn is_stub(source: &str) -> bool {
+    source.contains("Hello, world!")
+}
+
+fn load_runs(root: &Path) -> HashMap<(u16, u8), Run> {
+    let Ok(contents) = fs::read_to_string(root.join(RUNS_FILE)) else {
+        return HashMap::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let year = fields.next()?.parse().ok()?;
+            let day = fields.next()?.parse().ok()?;
+            let passed = fields.next()? == "pass";
+            let runtime = fields
+                .next()
+                .and_then(|millis| millis.parse().ok())
+                .map(Duration::from_millis);
+            let answers = fields
+                .next()
+                .and_then(|answers| answers.parse().ok())
+                .unwrap_or(0);
+            Some((
+                (year, day),
+                Run {
+                    passed,
+                    runtime,
+                    answers,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn save_runs(root: &Path, days: &[Day]) {
+    let contents: String = days
+        .iter()
+        .filter_map(|day| {
+            let run = day.last_run?;
+            Some(format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                day.year,
+                day.day,
+                if run.passed { "pass" } else { "fail" },
+                run.runtime
+                    .map_or(String::from("-"), |r| r.as_millis().to_string()),
+                run.answers,
+            ))
+        })
+        .collect();
+
+    let path = root.join(RUNS_FILE);
+    fs::create_dir_all(path.parent().unwrap()).expect("Failed to create runs directory");
+    fs::write(path, contents).expect("Failed to save runs");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Lay out a fake repo in a temporary directory
+    fn fixture(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aoc_report_{name}"));
+        let _ = fs::remove_dir_all(&root);
+        for (path, contents) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn stubs() {
+        assert!(is_stub(
+            "fn main() {\n    println!(\"Hello, world!\");\n}\n"
+        ));
+        assert!(!is_stub(
+            "fn main() {\n    println!(\"{}\", answer());\n}\n"
+        ));
+    }
+
+    #[test]
+    fn report() {
+        let root = fixture(
+            "report",
+            &[
+                (
+                    "2022/01/src/main.rs",
+                    "fn main() {\n    println!(\"a\");\n    println!(\"b\");\n}\n\n#[cfg(test)]\n#[test]\nfn test() {}\n",
+                ),
+                ("2022/02/src/main.rs", "fn main() {\n    println!(\"a\");\n}\n"),
+                ("2022/07/main.ts", "console.log('a')\nconsole.log('b')\n"),
+                ("2022/07/node_modules/x/index.ts", "test('ignored')\n"),
+                ("2023/12/src/main.rs", "fn main() {\n    println!(\"Hello, world!\");\n}\n"),
+                (".aoc/runs.tsv", "2022\t1\tpass\t1500\n"),
+                // Stars only come from correct answers, however many the code prints
+                (
+                    ".aoc/submissions.tsv",
+                    "2022\t1\t1\t10\tcorrect\n2022\t1\t2\t20\tcorrect\n2022\t7\t1\t5\tcorrect\n2022\t7\t2\t6\ttoo-low\n",
+                ),
+                ("notes/readme.rs", ""),
+            ],
+        );
+
+        assert_eq!(
+            render(&scan(&root)),
+            "# Advent of Code progress
+
+## 2022 (3/50 stars)
+
+| Day | Status | Languages | Tests | Runtime |
+| --: | ------ | --------- | ----: | ------: |
+| [01](2022/01) | complete | Rust | 1 | 1.50 s |
+| [02](2022/02) | in progress | Rust | 0 | - |
+| [07](2022/07) | part 1 | TypeScript | 0 | - |
+
+## 2023 (0/50 stars)
+
+| Day | Status | Languages | Tests | Runtime |
+| --: | ------ | --------- | ----: | ------: |
+| [12](2023/12) | not started | Rust | 0 | - |
+"
+        );
+    }
+    #[test]
+    fn parts_without_submissions() {
+        let root = fixture(
+            "parts_without_submissions",
+            &[
+                (
+                    "2022/01/src/main.rs",
+                    "fn main() {}\n\n#[test]\nfn part_1() {}\n\n#[test]\nfn part_2_example() {}\n",
+                ),
+                ("2022/02/src/main.rs", "fn main() {}\n"),
+                ("2022/03/src/main.rs", "fn main() {}\n"),
+                ("2022/04/src/main.rs", "fn part1() {}\n"),
+                ("2022/05/main.py", "def part_1():\n    pass\n"),
+                // Runs saved before answers were counted have no last column
+                (
+                    ".aoc/runs.tsv",
+                    "2022\t2\tpass\t10\t1\n2022\t3\tpass\t10\n2022\t4\tfail\t-\t0\n",
+                ),
+                // A wrong answer means part 1 isn't solved, whatever the code says
+                (".aoc/submissions.tsv", "2022\t5\t1\t9\ttoo-high\n"),
+            ],
+        );
+
+        let days = scan(&root);
+        let statuses: Vec<&str> = days.iter().map(Day::status).collect();
+        assert_eq!(
+            statuses,
+            [
+                "complete",
+                "part 1",
+                "in progress",
+                "failing",
+                "in progress"
+            ]
+        );
+        assert!(render(&days).contains("## 2022 (3/50 stars)"));
+    }
+
+    #[test]
+    fn answers() {
+        assert_eq!(
+            count_answers("Part 1: 5\n\nPart 2: 7\nPart 2: 7\nDone\n"),
+            2
+        );
+        assert_eq!(count_answers("5\n5\n"), 1);
+        assert_eq!(count_answers("\n"), 0);
+    }
+}
@@ -0,0 +1,433 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Where every submission outcome is recorded, relative to the repo root
+const SUBMISSIONS_FILE: &str = ".aoc/submissions.tsv";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after a previous answer, with how long is left to wait
+    RateLimited(Duration),
+    /// The level has already been solved, or isn't unlocked yet
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    /// Work out the outcome from the page the website responds with
+    fn parse(html: &str) -> Self {
+        if html.contains("That's the right answer") {
+            Self::Correct
+        } else if html.contains("That's not the right answer") {
+            if html.contains("your answer is too high") {
+                Self::TooHigh
+            } else if html.contains("your answer is too low") {
+                Self::TooLow
+            } else {
+                Self::Incorrect
+            }
+        } else if html.contains("You gave an answer too recently") {
+            Self::RateLimited(parse_wait(html).unwrap_or_default())
+        } else if html.contains("You don't seem to be solving the right level") {
+            Self::WrongLevel
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// The name used in the submissions file, only for outcomes worth remembering
+    fn record_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Correct => Some("correct"),
+            Self::Incorrect => Some("incorrect"),
+            Self::TooHigh => Some("too-high"),
+            Self::TooLow => Some("too-low"),
+            _ => None,
+        }
+    }
+
+    fn from_record_name(name: &str) -> Option<Self> {
+        match name {
+            "correct" => Some(Self::Correct),
+            "incorrect" => Some(Self::Incorrect),
+            "too-high" => Some(Self::TooHigh),
+            "too-low" => Some(Self::TooLow),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "That's the right answer!"),
+            Self::Incorrect => write!(f, "That's not the right answer"),
+            Self::TooHigh => write!(f, "That's not the right answer, it's too high"),
+            Self::TooLow => write!(f, "That's not the right answer, it's too low"),
+            Self::RateLimited(wait) => write!(
+                f,
+                "Answered too recently, wait {}m {}s",
+                wait.as_secs() / 60,
+                wait.as_secs() % 60
+            ),
+            Self::WrongLevel => write!(f, "That level is already solved or not unlocked"),
+            Self::Unknown => write!(f, "Couldn't understand the response"),
+        }
+    }
+}
+
+/// Find the "You have 1m 23s left to wait" part of a rate limit message
+fn parse_wait(html: &str) -> Option<Duration> {
+    let start = html.find("You have ")? + "You have ".len();
+    let end = start + html[start..].find(" left to wait")?;
+
+    html[start..end]
+        .split_whitespace()
+        .map(|part| {
+            let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let number: u64 = number.parse().ok()?;
+            match unit {
+                "h" => Some(number * 60 * 60),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// This level has already been answered correctly
+    AlreadySolved(String),
+    /// This answer is already known to be wrong, with the reason
+    KnownWrong(Outcome),
+    Http(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadySolved(answer) => {
+                write!(f, "This level was already solved with {answer}")
+            }
+            Self::KnownWrong(outcome) => {
+                write!(
+                    f,
+                    "Not submitting, this answer is known to be wrong: {outcome}"
+                )
+            }
+            Self::Http(error) => write!(f, "Failed to submit: {error}"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Submission {
+    year: u16,
+    day: u8,
+    level: u8,
+    answer: String,
+    outcome: Outcome,
+}
+
+/// Previously submitted answers, used to avoid resubmitting known-wrong ones
+pub struct History {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    pub fn load(root: &Path) -> Self {
+        let path = root.join(SUBMISSIONS_FILE);
+        let submissions = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                Some(Submission {
+                    year: fields.next()?.parse().ok()?,
+                    day: fields.next()?.parse().ok()?,
+                    level: fields.next()?.parse().ok()?,
+                    answer: fields.next()?.to_owned(),
+                    outcome: Outcome::from_record_name(fields.next()?)?,
+                })
+            })
+            .collect();
+
+        Self { path, submissions }
+    }
+
+    /// Whether any answer has been submitted for a day, right or wrong
+    pub fn submitted(&self, year: u16, day: u8) -> bool {
+        self.submissions
+            .iter()
+            .any(|s| s.year == year && s.day == day)
+    }
+
+    /// How many levels of a day have had a correct answer, which is how many stars it has
+    pub fn stars(&self, year: u16, day: u8) -> usize {
+        let mut levels: Vec<u8> = self
+            .submissions
+            .iter()
+            .filter(|s| s.year == year && s.day == day && s.outcome == Outcome::Correct)
+            .map(|s| s.level)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        levels.len()
+    }
+
+    /// Check a new answer against everything we already know about this level
+    fn check(&self, year: u16, day: u8, level: u8, answer: &str) -> Result<(), SubmitError> {
+        let previous = self
+            .submissions
+            .iter()
+            .filter(|s| s.year == year && s.day == day && s.level == level);
+
+        for submission in previous {
+            if submission.outcome == Outcome::Correct {
+                return Err(SubmitError::AlreadySolved(submission.answer.clone()));
+            }
+            if submission.answer == answer {
+                return Err(SubmitError::KnownWrong(submission.outcome));
+            }
+
+            // A numeric answer can also be ruled out by an earlier bound
+            if let (Ok(new), Ok(old)) = (answer.parse::<i64>(), submission.answer.parse::<i64>()) {
+                match submission.outcome {
+                    Outcome::TooHigh if new >= old => {
+                        return Err(SubmitError::KnownWrong(Outcome::TooHigh))
+                    }
+                    Outcome::TooLow if new <= old => {
+                        return Err(SubmitError::KnownWrong(Outcome::TooLow))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record(&mut self, submission: Submission) {
+        let Some(name) = submission.outcome.record_name() else {
+            return;
+        };
+
+        let mut contents = fs::read_to_string(&self.path).unwrap_or_default();
+        contents += &format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            submission.year, submission.day, submission.level, submission.answer, name
+        );
+        fs::create_dir_all(self.path.parent().unwrap())
+            .expect("Failed to create submissions directory");
+        fs::write(&self.path, contents).expect("Failed to record submission");
+
+        self.submissions.push(submission);
+    }
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(session: &str) -> Self {
+        Self::with_base_url("https://adventofcode.com", session)
+    }
+
+    fn with_base_url(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.trim().to_owned(),
+        }
+    }
+
+    /// Submit an answer, unless the history already rules it out, and record the outcome
+    pub fn submit(
+        &self,
+        history: &mut History,
+        year: u16,
+        day: u8,
+        level: u8,
+        answer: &str,
+    ) -> Result<Outcome, SubmitError> {
+        let answer = answer.trim();
+        history.check(year, day, level, answer)?;
+
+        let response = ureq::post(&format!("{}/{year}/day/{day}/answer", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/GRA0007/advent-of-code")
+            .send_form(&[("level", &level.to_string()), ("answer", answer)])
+            .map_err(|error| SubmitError::Http(error.to_string()))?
+            .into_string()
+            .map_err(|error| SubmitError::Http(error.to_string()))?;
+
+        let outcome = Outcome::parse(&response);
+        history.record(Submission {
+            year,
+            day,
+            level,
+            answer: answer.to_owned(),
+            outcome,
+        });
+
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use super::*;
+
+    /// Stand in for the website: answer a single request with `body`, and
+    /// hand back the raw request that was received
+    fn mock_server(body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            loop {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length: usize = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("Content-Length: "))
+                        .map_or(0, |length| length.parse().unwrap());
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            String::from_utf8(request).unwrap()
+        });
+
+        (url, handle)
+    }
+
+    fn history(name: &str) -> History {
+        let root = std::env::temp_dir().join(format!("aoc_submit_{name}"));
+        let _ = fs::remove_dir_all(&root);
+        History::load(&root)
+    }
+
+    #[test]
+    fn posts_answer() {
+        let (url, server) = mock_server(
+            "<article><p>That's the right answer! You are one gold star closer.</p></article>",
+        );
+        let mut history = history("posts_answer");
+
+        let outcome = Client::with_base_url(&url, "abc123")
+            .submit(&mut history, 2015, 4, 1, "117946\n")
+            .unwrap();
+        assert_eq!(outcome, Outcome::Correct);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2015/day/4/answer HTTP/1.1"));
+        assert!(request.contains("Cookie: session=abc123"));
+        assert!(request.ends_with("level=1&answer=117946"));
+    }
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(
+            Outcome::parse("That's not the right answer; your answer is too high."),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            Outcome::parse("That's not the right answer; your answer is too low."),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            Outcome::parse("That's not the right answer. If you're stuck, make sure..."),
+            Outcome::Incorrect
+        );
+        assert_eq!(
+            Outcome::parse("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait."),
+            Outcome::RateLimited(Duration::from_secs(83))
+        );
+        assert_eq!(
+            Outcome::parse("You gave an answer too recently. You have 35s left to wait."),
+            Outcome::RateLimited(Duration::from_secs(35))
+        );
+        assert_eq!(
+            Outcome::parse(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Outcome::WrongLevel
+        );
+    }
+
+    #[test]
+    fn skips_known_wrong_answers() {
+        let (url, server) = mock_server("That's not the right answer; your answer is too high.");
+        let mut history = history("skips_known_wrong_answers");
+        let client = Client::with_base_url(&url, "abc123");
+
+        assert_eq!(
+            client.submit(&mut history, 2022, 1, 2, "500").unwrap(),
+            Outcome::TooHigh
+        );
+        server.join().unwrap();
+
+        // Nothing is listening any more, so these must be rejected locally
+        assert!(matches!(
+            client.submit(&mut history, 2022, 1, 2, "500"),
+            Err(SubmitError::KnownWrong(Outcome::TooHigh))
+        ));
+        assert!(matches!(
+            client.submit(&mut history, 2022, 1, 2, "501"),
+            Err(SubmitError::KnownWrong(Outcome::TooHigh))
+        ));
+
+        // The outcome survives a reload
+        let history = History::load(&history.path.parent().unwrap().join(".."));
+        assert!(history.check(2022, 1, 2, "600").is_err());
+        assert!(history.check(2022, 1, 1, "600").is_ok());
+    }
+
+    #[test]
+    fn rate_limits_are_not_recorded() {
+        let (url, server) =
+            mock_server("You gave an answer too recently. You have 10s left to wait.");
+        let mut history = history("rate_limits_are_not_recorded");
+
+        Client::with_base_url(&url, "abc123")
+            .submit(&mut history, 2023, 12, 1, "21")
+            .unwrap();
+        server.join().unwrap();
+
+        assert!(history.check(2023, 12, 1, "21").is_ok());
+    }
+}